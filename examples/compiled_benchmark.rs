@@ -0,0 +1,52 @@
+//! Compares the time needed to evaluate an expression many times, with different
+//! variable values, through `eval_with_static_context` and a `CompiledExpression`.
+//!
+//! Run with `cargo run --release --example compiled_benchmark`.
+
+use std::{collections::HashMap, time::Instant};
+
+use num_parser2::*;
+
+const INPUT: &str = "3x^2 + 2sin(x) - x/(1 + y)";
+const ITERATIONS: i64 = 10_000;
+
+fn main() {
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        let mut context = Context::default();
+        context.add_variable(String::from("x"), Box::new(Expression::Literal(Value::from(i))));
+        context.add_variable(String::from("y"), Box::new(Expression::Literal(Value::from(2))));
+        eval_with_static_context(INPUT, &context).unwrap();
+    }
+    let interpreted = start.elapsed();
+
+    let start = Instant::now();
+    let expression = parse(INPUT).unwrap();
+    let context = Context::default();
+    for i in 0..ITERATIONS {
+        let mut context = context.clone();
+        context.add_variable(String::from("x"), Box::new(Expression::Literal(Value::from(i))));
+        context.add_variable(String::from("y"), Box::new(Expression::Literal(Value::from(2))));
+        expression.eval(&context, None, 0).unwrap();
+    }
+    let parsed_once = start.elapsed();
+
+    let start = Instant::now();
+    let compiled = parse(INPUT).unwrap().compile(&Context::default());
+    let mut vars = HashMap::new();
+    vars.insert(String::from("y"), Value::from(2));
+    for i in 0..ITERATIONS {
+        vars.insert(String::from("x"), Value::from(i));
+        compiled.eval_compiled(&vars).unwrap();
+    }
+    let compiled_time = start.elapsed();
+
+    println!("{} evaluations of `{}`:", ITERATIONS, INPUT);
+    println!("eval_with_static_context: {:?}", interpreted);
+    println!("Expression::eval:         {:?}", parsed_once);
+    println!(
+        "eval_compiled:            {:?} ({:.1}x faster than Expression::eval)",
+        compiled_time,
+        parsed_once.as_secs_f64() / compiled_time.as_secs_f64()
+    );
+}
@@ -0,0 +1,120 @@
+use crate::{api::parse, objects::Expression, out::EvalResult, prelude::*};
+
+/// A least recently used cache of parsed expressions, keyed by their input.
+///
+/// Expressions are parsed with `parse`, which does not depend on any context, so
+/// cached expressions never need to be invalidated.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let mut cache = ParserCache::new(2);
+///
+/// let first = cache.parse_cached("2x + 1").unwrap();
+/// let second = cache.parse_cached("2x + 1").unwrap();
+///
+/// assert_eq!(first, second);
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+///
+/// // The least recently used expression is removed when the capacity is exceeded
+/// cache.parse_cached("y").unwrap();
+/// cache.parse_cached("2x + 1").unwrap();
+/// cache.parse_cached("z").unwrap();
+/// assert!(cache.contains("2x + 1"));
+/// assert!(!cache.contains("y"));
+/// assert_eq!(cache.len(), 2);
+///
+/// // Errors are not cached
+/// assert!(cache.parse_cached("2 +").is_err());
+/// assert!(!cache.contains("2 +"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParserCache {
+    /// The parsed expressions, with the time they were last used.
+    entries: HashMap<String, (Expression, u64)>,
+    capacity: usize,
+    /// Incremented every time the cache is used.
+    time: u64,
+    hits: usize,
+    misses: usize,
+}
+
+impl ParserCache {
+    /// Creates an empty cache holding up to `capacity` expressions.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::with_capacity(capacity),
+            capacity,
+            time: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the parsed input, parsing it only if it is not cached.
+    pub fn parse_cached(&mut self, input: &str) -> EvalResult<Expression> {
+        self.time += 1;
+
+        if let Some((expression, last_used)) = self.entries.get_mut(input) {
+            *last_used = self.time;
+            self.hits += 1;
+            return Ok(expression.clone());
+        }
+
+        self.misses += 1;
+        let expression = parse(input)?;
+
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                let least_recently_used = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, (_, last_used))| *last_used)
+                    .map(|(input, _)| input.clone());
+                if let Some(input) = least_recently_used {
+                    self.entries.remove(&input);
+                }
+            }
+            self.entries
+                .insert(String::from(input), (expression.clone(), self.time));
+        }
+
+        Ok(expression)
+    }
+
+    /// Returns true if the parsed input is cached.
+    pub fn contains(&self, input: &str) -> bool {
+        self.entries.contains_key(input)
+    }
+
+    /// Returns the amount of cached expressions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no expression is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the maximum amount of cached expressions.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the amount of inputs found in the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Returns the amount of inputs which had to be parsed.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Removes all the cached expressions.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
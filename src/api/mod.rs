@@ -1,4 +1,24 @@
-use crate::{context::Context, interpreter, out::*, token, tree, value::Value};
+mod cache;
+
+pub use self::cache::ParserCache;
+
+use core::ops::Range;
+
+use crate::{
+    context::Context,
+    function::{builtin::get_built_in_function, Arguments},
+    interpreter,
+    objects::Expression,
+    out::*,
+    prelude::*,
+    token::{
+        self,
+        tokentype::{IdentifierType, TokenType},
+        Token,
+    },
+    tree,
+    value::Value,
+};
 
 /// Evaluate an expression with the default context.
 ///
@@ -28,6 +48,91 @@ pub fn eval(input: &str) -> EvalResult<Value> {
     eval_with_static_context(input, &Context::default())
 }
 
+/// Parse an expression with the default context, without evaluating it.
+///
+/// See also `parse_with_context`.
+///
+/// Declarations are returned as a binary `=` expression.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let expression = parse("x + 1").unwrap();
+///
+/// assert_eq!(
+///     expression,
+///     Expression::Binary(
+///         Box::new(Expression::Var(String::from("x"))),
+///         TokenType::Plus,
+///         Box::new(Expression::Literal(Value::from(1))),
+///     )
+/// );
+/// ```
+pub fn parse(input: &str) -> EvalResult<Expression> {
+    parse_with_context(input, &Context::default())
+}
+
+/// Parse an expression without evaluating it.
+///
+/// See also `parse`.
+///
+/// The context is used to identify user-defined functions and variables, so that
+/// identifiers like `ab` are not split into `a*b` if `ab` was declared.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// context.add_variable(String::from("ab"), Box::new(Expression::Literal(Value::from(2))));
+///
+/// assert_eq!(
+///     parse_with_context("ab", &context).unwrap(),
+///     Expression::Var(String::from("ab"))
+/// );
+/// assert_ne!(parse("ab").unwrap(), Expression::Var(String::from("ab")));
+/// ```
+pub fn parse_with_context(input: &str, context: &Context) -> EvalResult<Expression> {
+    let stream = token::build_stream(String::from(input), context)?;
+    let tree = tree::build_tree(stream)?;
+
+    Ok(tree.0)
+}
+
+/// Evaluate all the parts of an expression which do not depend on unknown variables
+/// or functions, leaving the rest untouched.
+///
+/// See also `parse`.
+///
+/// Unlike the other evaluation functions, unknown identifiers are not errors, while
+/// any other error is returned. Values are not rounded.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// eval_with_mutable_context("a = 4", &mut context).unwrap();
+///
+/// let expression = parse("3*2 + x").unwrap();
+/// assert_eq!(eval_partial(&expression, &context).unwrap().to_string(), "6 + x");
+///
+/// let expression = parse("a*x^(2 - 1) + max(a, 1, y)").unwrap();
+/// assert_eq!(eval_partial(&expression, &context).unwrap().to_string(), "4*x^1 + max(4, 1, y)");
+///
+/// // Fully known expressions become literals
+/// assert_eq!(
+///     eval_partial(&parse("2a + 1").unwrap(), &context).unwrap(),
+///     Expression::Literal(Value::from(9))
+/// );
+/// // Other errors are returned
+/// assert!(eval_partial(&parse("x + 1/0").unwrap(), &context).is_err());
+/// ```
+pub fn eval_partial(expression: &Expression, context: &Context) -> EvalResult<Expression> {
+    expression.eval_partial(context)
+}
+
 /// Evaluate an expression not allowing context changes.
 ///
 /// See also `eval`  and`eval_with_mutable_context`.
@@ -62,6 +167,9 @@ pub fn eval_with_static_context(input: &str, context: &Context) -> EvalResult<Va
     }
 
     let mut context_clone = context.clone();
+    // Evaluations of a shared context, possibly from other threads, must not see each
+    // other's variables being resolved
+    context_clone.resolving = Arc::default();
 
     match request.execute(&mut context_clone)? {
         Some(result) => Ok(result),
@@ -94,6 +202,16 @@ pub fn eval_with_static_context(input: &str, context: &Context) -> EvalResult<Va
 ///
 /// assert_eq!(res, Some(Value::from(5)));
 ///
+/// // The parameters of a function are stored with its body
+/// eval_with_mutable_context("f(x, y) = x + y", &mut context).unwrap();
+/// assert_eq!(context.functions["f"].0, vec!["x", "y"]);
+/// assert_eq!(eval_with_mutable_context("f(2, 3)", &mut context).unwrap(), Some(Value::from(5)));
+///
+/// // Declarations need a mutable context
+/// assert!(matches!(
+///     eval_with_static_context("h(x) = x", &context),
+///     Err(ErrorType::InvalidMutableContext { .. })
+/// ));
 /// ```
 pub fn eval_with_mutable_context(input: &str, context: &mut Context) -> EvalResult<Option<Value>> {
     let input = String::from(input);
@@ -103,3 +221,245 @@ pub fn eval_with_mutable_context(input: &str, context: &mut Context) -> EvalResu
 
     request.execute(context)
 }
+
+/// Evaluate multiple expressions separated by semicolons, using the provided context.
+///
+/// See also `eval_with_mutable_context`.
+///
+/// Statements are executed in order, so declarations are available to the following
+/// ones. Empty statements are skipped. Returns the result of the last evaluation, or
+/// `Ok(None)` if there was none.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+///
+/// let res = eval_script("f(x) = x^2; a = 2;; f(3) + a;", &mut context).unwrap();
+///
+/// assert_eq!(res, Some(Value::from(11)));
+/// // Declarations persist into the context
+/// assert_eq!(eval_with_static_context("f(a)", &context).unwrap(), Value::from(4));
+/// assert_eq!(eval_script("b = 1", &mut context).unwrap(), None);
+/// ```
+pub fn eval_script(input: &str, context: &mut Context) -> EvalResult<Option<Value>> {
+    let mut last_result = None;
+
+    for statement in input.split(';') {
+        if statement.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(result) = eval_with_mutable_context(statement, context)? {
+            last_result = Some(result);
+        }
+    }
+
+    Ok(last_result)
+}
+
+/// Evaluate many expressions independently, using the provided context.
+///
+/// See also `eval_with_static_context`.
+///
+/// Returns the result of every expression, in the same order, so an error only
+/// affects its own expression.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// context.define("a = 3").unwrap();
+///
+/// let results = eval_all(&["a + 1", "2 +", "1/0", "a^2"], &context);
+///
+/// assert_eq!(results.len(), 4);
+/// assert_eq!(results[0].as_ref().unwrap(), &Value::from(4));
+/// assert!(matches!(results[1], Err(ErrorType::MissingOperatorArgument { .. })));
+/// assert!(matches!(results[2], Err(ErrorType::DivideByZero { .. })));
+/// assert_eq!(results[3].as_ref().unwrap(), &Value::from(9));
+/// ```
+pub fn eval_all(inputs: &[&str], context: &Context) -> Vec<EvalResult<Value>> {
+    inputs
+        .iter()
+        .map(|input| eval_with_static_context(input, context))
+        .collect()
+}
+
+/// Splits an input into tokens, without parsing it. Tokens carry their byte range
+/// inside the input, while tokens added while tokenizing, like implicit
+/// multiplications, have an empty range.
+///
+/// Declarations are not known, so identifiers are split as with an empty context. The
+/// input is tokenized at once, rather than lazily. If it cannot be tokenized, the tokens
+/// before the part the error refers to are yielded, if any, and the error is the last
+/// item.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let tokens = tokenize("sin(2x)")
+///     .map(|token| token.map(|token| (token.r#type, token.value, token.span)))
+///     .collect::<EvalResult<Vec<_>>>()
+///     .unwrap();
+///
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         (TokenType::Identifier(IdentifierType::Function), String::from("sin"), 0..3),
+///         (TokenType::OpeningBracket, String::new(), 3..4),
+///         (TokenType::Literal, String::from("2"), 4..5),
+///         (TokenType::Star, String::new(), 5..5),
+///         (TokenType::Identifier(IdentifierType::Var), String::from("x"), 5..6),
+///         (TokenType::ClosingBracket, String::new(), 6..7),
+///     ]
+/// );
+///
+/// let types = tokenize("sin(2*x)")
+///     .map(|token| token.unwrap().r#type)
+///     .collect::<Vec<TokenType>>();
+/// assert_eq!(
+///     types,
+///     vec![
+///         TokenType::Identifier(IdentifierType::Function),
+///         TokenType::OpeningBracket,
+///         TokenType::Literal,
+///         TokenType::Star,
+///         TokenType::Identifier(IdentifierType::Var),
+///         TokenType::ClosingBracket,
+///     ]
+/// );
+///
+/// let mut tokens = tokenize("2 + $ 3");
+/// assert_eq!(tokens.next().unwrap().unwrap().r#type, TokenType::Literal);
+/// assert_eq!(tokens.next().unwrap().unwrap().r#type, TokenType::Plus);
+/// assert!(matches!(tokens.next(), Some(Err(ErrorType::UnknownToken { .. }))));
+/// assert!(tokens.next().is_none());
+///
+/// let mut tokens = tokenize("$");
+/// assert!(matches!(tokens.next(), Some(Err(ErrorType::UnknownToken { .. }))));
+/// assert!(tokens.next().is_none());
+/// ```
+pub fn tokenize(input: &str) -> impl Iterator<Item = EvalResult<Token>> {
+    let context = Context::default();
+    let (tokens, error) = match token::build_stream(String::from(input), &context) {
+        Ok(tokens) => (tokens, None),
+        Err(error) => {
+            // The input before the error is tokenized on its own
+            let tokens = error
+                .span()
+                .and_then(|span| token::build_stream(String::from(&input[..span.start]), &context).ok())
+                .unwrap_or_default();
+            (tokens, Some(error))
+        }
+    };
+    tokens.into_iter().map(Ok).chain(error.map(Err))
+}
+
+/// Parse an expression written in reverse Polish notation, with the default context.
+///
+/// See also `parse`.
+///
+/// Tokens are separated by whitespaces. Operands are parsed like infix expressions,
+/// operators take the two values on top of the stack, and built-in functions take
+/// as many values as they expect. Functions expecting any amount of arguments take
+/// a single list. Use `neg` to negate a value, `not` for the logical negation and
+/// `!` for the factorial.
+///
+/// The resulting expression is the same that the equivalent infix expression builds.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// assert_eq!(parse_rpn("3 4 + 2 *").unwrap(), parse("(3 + 4)*2").unwrap());
+/// assert_eq!(parse_rpn("2 3 4 ^ ^").unwrap(), parse("2^(3^4)").unwrap());
+/// assert_eq!(parse_rpn("x 2 ^ 1 x - /").unwrap(), parse("x^2/(1 - x)").unwrap());
+/// assert_eq!(parse_rpn("x sin 2 atan2 neg").unwrap(), parse("-atan2(sin(x), 2)").unwrap());
+/// assert_eq!(parse_rpn("5 ! [1,2,3] max <=").unwrap(), parse("5! <= max([1,2,3])").unwrap());
+/// assert_eq!(parse_rpn("x 1 < not").unwrap(), parse("!(x < 1)").unwrap());
+///
+/// assert_eq!(eval_partial(&parse_rpn("3 4 + 2 *").unwrap(), &Context::default()).unwrap(),
+///     Expression::Literal(Value::from(14)));
+///
+/// // Too few operands
+/// assert!(matches!(
+///     parse_rpn("3 +"),
+///     Err(ErrorType::MissingOperatorArgument { token: TokenType::Plus, span }) if span == (2..3)
+/// ));
+/// // Too many operands
+/// assert!(matches!(
+///     parse_rpn("3 4 5 +"),
+///     Err(ErrorType::MissingOperator { span }) if span == (0..1)
+/// ));
+/// ```
+pub fn parse_rpn(input: &str) -> EvalResult<Expression> {
+    let context = Context::default();
+    // Every stack element keeps the byte range of its tokens.
+    let mut stack: Vec<(Expression, Range<usize>)> = vec![];
+
+    for word in input.split_whitespace() {
+        let start = word.as_ptr() as usize - input.as_ptr() as usize;
+        let span = start..start + word.len();
+
+        let (token, arguments) = match word {
+            "neg" => (TokenType::Minus, 1),
+            "not" => (TokenType::Exclamation, 1),
+            // Lone bars would be read as absolute value brackets
+            "|" => (TokenType::Or, 2),
+            "||" => (TokenType::DoubleOr, 2),
+            "!" => (TokenType::Factorial, 1),
+            _ => match get_built_in_function(word) {
+                Some(function) => (
+                    TokenType::Identifier(IdentifierType::Function),
+                    match function.args {
+                        Arguments::Const(amount) => amount,
+                        Arguments::Dynamic => 1,
+                    },
+                ),
+                None => match &token::build_stream(String::from(word), &context)?[..] {
+                    [token] if token.r#type.is_binary_operator() => (token.r#type, 2),
+                    _ => {
+                        stack.push((parse_with_context(word, &context)?, span));
+                        continue;
+                    }
+                },
+            },
+        };
+
+        if stack.len() < arguments {
+            return Err(ErrorType::MissingOperatorArgument { token, span });
+        }
+        let operands = stack.split_off(stack.len() - arguments);
+        let span = operands.first().map_or(span.start, |(_, span)| span.start)..span.end;
+        let mut operands = operands.into_iter().map(|(expression, _)| Box::new(expression));
+
+        let expression = match token {
+            TokenType::Identifier(_) => {
+                let arguments = match operands.collect::<Vec<Box<Expression>>>() {
+                    // A single list is spread into the arguments, as in infix expressions
+                    arguments if arguments.len() == 1 => match *arguments[0].clone() {
+                        Expression::Union(elements) => elements,
+                        _ => arguments,
+                    },
+                    arguments => arguments,
+                };
+                Expression::Func(String::from(word), arguments)
+            }
+            _ if arguments == 1 => Expression::Unary(token, operands.next().unwrap()),
+            _ => Expression::Binary(operands.next().unwrap(), token, operands.next().unwrap()),
+        };
+        stack.push((expression, span));
+    }
+
+    match stack.len() {
+        0 => parse_with_context(input, &context),
+        1 => Ok(stack.pop().unwrap().0),
+        _ => Err(ErrorType::MissingOperator {
+            span: stack[0].1.clone(),
+        }),
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{context::Context, context::settings, objects::Expression, prelude::*};
+
+/// Builds a [`Context`] setting only the needed settings, while the other ones keep
+/// their default value. It is created with `Context::builder`.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let context = Context::builder()
+///     .rounding(settings::Rounding::Round(2))
+///     .angle_unit(settings::AngleUnit::Degree)
+///     .depth_limit(settings::DepthLimit::Limit(100))
+///     .iteration_limit(settings::IterationLimit::Limit(1000))
+///     .step_limit(10_000)
+///     .memoize(true)
+///     .function("f", &["x", "y"], parse("x^2 + y").unwrap())
+///     .variable("a", parse("3").unwrap())
+///     .build();
+///
+/// assert!(matches!(context.rounding, settings::Rounding::Round(2)));
+/// assert!(matches!(context.depth_limit, settings::DepthLimit::Limit(100)));
+/// assert_eq!(context.step_limit, Some(10_000));
+/// assert!(context.memoize);
+///
+/// assert_eq!(eval_with_static_context("f(a, 1)", &context).unwrap(), Value::from(10));
+/// assert_eq!(eval_with_static_context("sin(30)", &context).unwrap(), Value::from(0.5));
+/// assert_eq!(eval_with_static_context("pi", &context).unwrap(), Value::from(3.14));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ContextBuilder {
+    context: Context,
+}
+
+impl ContextBuilder {
+    /// Creates a builder with the default settings and no declarations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the decimal digits to display.
+    pub fn rounding(mut self, rounding: settings::Rounding) -> Self {
+        self.context.rounding = rounding;
+        self
+    }
+
+    /// Sets the angle unit to use.
+    pub fn angle_unit(mut self, angle_unit: settings::AngleUnit) -> Self {
+        self.context.angle_unit = angle_unit;
+        self
+    }
+
+    /// Sets the depth limit for recursion control.
+    pub fn depth_limit(mut self, depth_limit: settings::DepthLimit) -> Self {
+        self.context.depth_limit = depth_limit;
+        self
+    }
+
+    /// Sets the iteration limit for functions like `sum` and `product`.
+    pub fn iteration_limit(mut self, iteration_limit: settings::IterationLimit) -> Self {
+        self.context.iteration_limit = iteration_limit;
+        self
+    }
+
+    /// Sets the base used to display integer results.
+    pub fn output_base(mut self, output_base: settings::OutputBase) -> Self {
+        self.context.output_base = output_base;
+        self
+    }
+
+    /// Sets the separator used to group digits by thousands.
+    pub fn group_digits(mut self, group_digits: settings::GroupDigits) -> Self {
+        self.context.group_digits = group_digits;
+        self
+    }
+
+    /// Sets the maximum amount of expressions evaluated by a single evaluation.
+    pub fn step_limit(mut self, step_limit: u64) -> Self {
+        self.context.step_limit = Some(step_limit);
+        self
+    }
+
+    /// Sets the seed of the random functions.
+    pub fn rng_seed(mut self, rng_seed: u64) -> Self {
+        self.context.rng_seed = Some(rng_seed);
+        self
+    }
+
+    /// Sets whether to cache the results of user-defined functions.
+    pub fn memoize(mut self, memoize: bool) -> Self {
+        self.context.memoize = memoize;
+        self
+    }
+
+    /// Sets whether adjacent factors are multiplied.
+    pub fn implicit_multiplication(mut self, implicit_multiplication: bool) -> Self {
+        self.context.implicit_multiplication = implicit_multiplication;
+        self
+    }
+
+    /// Declares a function with its parameters and body.
+    pub fn function(mut self, identifier: &str, params: &[&str], body: Expression) -> Self {
+        self.context.add_function(
+            identifier.to_owned(),
+            params.iter().map(|param| param.to_string()).collect(),
+            Box::new(body),
+        );
+        self
+    }
+
+    /// Declares a variable with its expression.
+    pub fn variable(mut self, identifier: &str, expression: Expression) -> Self {
+        self.context.add_variable(identifier.to_owned(), Box::new(expression));
+        self
+    }
+
+    /// Returns the built context.
+    pub fn build(self) -> Context {
+        self.context
+    }
+}
@@ -1,11 +1,51 @@
-use std::collections::HashMap;
+use core::sync::atomic::AtomicUsize;
 
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+mod builder;
 pub mod settings;
 
-use crate::objects::Expression;
+pub use self::builder::ContextBuilder;
+
+use crate::{
+    interpreter,
+    objects::{Expression, Request},
+    out::ErrorType,
+    prelude::*,
+    token, tree,
+    value::Value,
+    EvalResult,
+};
 
 use self::settings::Rounding;
 
+/// A context shared between threads, which evaluate expressions with
+/// `eval_with_static_context`. Each evaluation works on its own copy of the context,
+/// so evaluations do not affect each other.
+///
+/// ```
+/// use num_parser2::*;
+/// use std::{sync::Arc, thread};
+///
+/// let mut context = Context::default();
+/// eval_script("f(x) = x^2; a = 10", &mut context).unwrap();
+/// let shared: SharedContext = Arc::new(context);
+///
+/// let handles = (1..=4)
+///     .map(|n| {
+///         let context = Arc::clone(&shared);
+///         thread::spawn(move || eval_with_static_context(&format!("f({}) + a", n), &context))
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let results = handles
+///     .into_iter()
+///     .map(|handle| handle.join().unwrap().unwrap())
+///     .collect::<Vec<Value>>();
+/// assert_eq!(results, vec![11, 14, 19, 26].into_iter().map(Value::from).collect::<Vec<_>>());
+/// ```
+pub type SharedContext = Arc<Context>;
+
 /// Contains user-defined functions and constants.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -22,6 +62,39 @@ pub struct Context {
     pub angle_unit: settings::AngleUnit,
     /// Depth limit for recursion control. .
     pub depth_limit: settings::DepthLimit,
+    /// Iteration limit for functions like `sum` and `product`.
+    pub iteration_limit: settings::IterationLimit,
+    /// The base used to display integer results.
+    pub output_base: settings::OutputBase,
+    /// The separator used to group digits by thousands when displaying results.
+    pub group_digits: settings::GroupDigits,
+    /// The maximum amount of expressions evaluated by a single evaluation, if any.
+    pub step_limit: Option<u64>,
+    /// The seed of the random functions. With a seed, the same sequence of random
+    /// numbers is generated during the context lifetime.
+    pub rng_seed: Option<u64>,
+    /// Whether to cache the results of user-defined functions during an evaluation.
+    pub memoize: bool,
+    /// Whether adjacent factors, like in `2x`, are multiplied. When disabled, they
+    /// return `ErrorType::MissingOperator`.
+    pub implicit_multiplication: bool,
+
+    /// The cached results of user-defined functions, shared by the clones made
+    /// during an evaluation.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) memo: Arc<Mutex<HashMap<String, Value>>>,
+    /// The amount of expressions evaluated during an evaluation, shared by the clones
+    /// made during it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) steps: Arc<AtomicUsize>,
+    /// The variables being resolved by the running evaluation, innermost last, shared
+    /// by the clones made during it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) resolving: Arc<Mutex<Vec<String>>>,
+    /// The seeded random number generator and its seed, shared by the clones of the
+    /// context.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) rng: Arc<Mutex<Option<(u64, StdRng)>>>,
 }
 
 impl Default for Context {
@@ -32,6 +105,17 @@ impl Default for Context {
             rounding: settings::Rounding::default(),
             angle_unit: settings::AngleUnit::default(),
             depth_limit: settings::DepthLimit::default(),
+            iteration_limit: settings::IterationLimit::default(),
+            output_base: settings::OutputBase::default(),
+            group_digits: settings::GroupDigits::default(),
+            step_limit: None,
+            rng_seed: None,
+            memoize: false,
+            implicit_multiplication: true,
+            memo: Arc::default(),
+            steps: Arc::default(),
+            resolving: Arc::default(),
+            rng: Arc::default(),
         }
     }
 }
@@ -49,6 +133,68 @@ impl Context {
             rounding,
             angle_unit,
             depth_limit,
+            iteration_limit: settings::IterationLimit::default(),
+            output_base: settings::OutputBase::default(),
+            group_digits: settings::GroupDigits::default(),
+            step_limit: None,
+            rng_seed: None,
+            memoize: false,
+            implicit_multiplication: true,
+            memo: Arc::default(),
+            steps: Arc::default(),
+            resolving: Arc::default(),
+            rng: Arc::default(),
+        }
+    }
+
+    /// Returns a builder to create a context with custom settings and declarations.
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+
+    /// Formats a value for display, rounding it first and then applying the output
+    /// base and the digits grouping.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// context.rounding = settings::Rounding::Round(2);
+    /// context.group_digits = settings::GroupDigits(Some(','));
+    ///
+    /// let value = eval_with_static_context("1234567.891", &context).unwrap();
+    ///
+    /// assert_eq!(context.format_value(&Value::from(1234567.891)).unwrap(), "1,234,567.89");
+    /// assert_eq!(context.format_value(&value).unwrap(), "1,234,567.89");
+    /// ```
+    pub fn format_value(&self, value: &Value) -> EvalResult<String> {
+        let formatted = self.output_base.format(&value.round(self.rounding))?;
+
+        Ok(match self.output_base {
+            settings::OutputBase::Decimal => self.group_digits.apply(&formatted),
+            _ => formatted,
+        })
+    }
+
+    /// Calls the function with the seeded random number generator, or with the thread
+    /// one if there is no seed. The seeded generator is created on first use, and
+    /// again whenever the seed changes.
+    ///
+    /// Without the `std` feature there is no source of entropy, so a missing seed
+    /// defaults to zero.
+    pub(crate) fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        let seed = match self.rng_seed {
+            Some(seed) => seed,
+            #[cfg(feature = "std")]
+            None => return f(&mut rand::thread_rng()),
+            #[cfg(not(feature = "std"))]
+            None => 0,
+        };
+
+        let mut rng = self.rng.lock().unwrap();
+        match &mut *rng {
+            Some((current_seed, generator)) if *current_seed == seed => f(generator),
+            other => f(&mut other.insert((seed, StdRng::seed_from_u64(seed))).1),
         }
     }
 
@@ -72,6 +218,228 @@ impl Context {
         self.variables.insert(identifier, expression);
     }
 
+    /// Parses a function or variable declaration, like `f(x) = x^2` or `k = 3.5`, and
+    /// adds it to the user-defined ones. Other expressions return
+    /// `ErrorType::InvalidDeclaration`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// context.define("f(x) = x^2").unwrap();
+    /// context.define("k = 3.5").unwrap();
+    ///
+    /// assert!(context.functions.contains_key("f"));
+    /// assert_eq!(eval_with_static_context("f(2) + k", &context).unwrap(), Value::from(7.5));
+    ///
+    /// assert!(matches!(context.define("2 + 3"), Err(ErrorType::InvalidDeclaration)));
+    /// assert!(context.define("g(x) = ").is_err());
+    /// assert!(context.define("f(x = 2").is_err());
+    /// ```
+    pub fn define(&mut self, input: &str) -> EvalResult<()> {
+        let stream = token::build_stream(String::from(input), self)?;
+        let tree = tree::build_tree(stream)?;
+
+        match interpreter::interpret_tree(&tree)? {
+            Request::Evaluation(_) => Err(ErrorType::InvalidDeclaration),
+            request => request.execute(self).map(|_| ()),
+        }
+    }
+
+    /// Serializes the declarations and the settings of the context as JSON.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// context.define("f(x) = x^2 + k").unwrap();
+    /// context.define("k = 1").unwrap();
+    /// context.angle_unit = settings::AngleUnit::Degree;
+    ///
+    /// let json = context.to_json().unwrap();
+    /// let restored = Context::from_json(&json).unwrap();
+    ///
+    /// assert_eq!(eval_with_static_context("f(2)", &restored).unwrap(), Value::from(5));
+    /// assert_eq!(eval_with_static_context("sin(90)", &restored).unwrap(), Value::from(1));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Restores a context serialized with `to_json`. As with any other declaration,
+    /// variables depending on themselves are accepted, and return
+    /// `ErrorType::CircularReference` once evaluated.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// context.define("g(x, y) = x*y - 1").unwrap();
+    /// context.define("a = b + 1").unwrap();
+    /// context.define("b = 2a").unwrap();
+    ///
+    /// let restored = Context::from_json(&context.to_json().unwrap()).unwrap();
+    /// assert!(restored.is_function("g"));
+    /// assert_eq!(eval_with_static_context("g(3, 4)", &restored).unwrap(), Value::from(11));
+    /// assert!(matches!(
+    ///     eval_with_static_context("a", &restored),
+    ///     Err(ErrorType::CircularReference { .. })
+    /// ));
+    ///
+    /// assert!(Context::from_json("{}").is_err());
+    /// assert!(Context::from_json("not json").is_err());
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Removes a user-defined function. Returns true if it existed.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("f(x) = 2x", &mut context).unwrap();
+    ///
+    /// assert!(context.remove_function("f"));
+    /// assert!(!context.is_function("f"));
+    /// assert!(!context.remove_function("f"));
+    /// ```
+    pub fn remove_function(&mut self, identifier: &str) -> bool {
+        self.functions.remove(identifier).is_some()
+    }
+
+    /// Removes a user-defined variable. Returns true if it existed.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("a = 2", &mut context).unwrap();
+    ///
+    /// assert!(context.remove_variable("a"));
+    /// assert!(!context.is_var("a"));
+    /// assert!(!context.remove_variable("a"));
+    /// ```
+    pub fn remove_variable(&mut self, identifier: &str) -> bool {
+        self.variables.remove(identifier).is_some()
+    }
+
+    /// Removes all the user-defined functions and variables, keeping the settings.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::new(
+    ///     settings::Rounding::Round(2),
+    ///     settings::AngleUnit::Degree,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    /// eval_with_mutable_context("f(x) = 2x", &mut context).unwrap();
+    /// eval_with_mutable_context("a = 2", &mut context).unwrap();
+    ///
+    /// context.clear();
+    ///
+    /// assert!(!context.is_function("f"));
+    /// assert!(!context.is_var("a"));
+    /// assert_eq!(eval_with_static_context("sin(30) + pi", &context).unwrap(), Value::from(3.64));
+    /// ```
+    pub fn clear(&mut self) {
+        self.clear_functions();
+        self.clear_variables();
+    }
+
+    /// Removes all the user-defined functions.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("f(x) = 2x", &mut context).unwrap();
+    /// eval_with_mutable_context("a = 2", &mut context).unwrap();
+    ///
+    /// context.clear_functions();
+    ///
+    /// assert!(!context.is_function("f"));
+    /// assert!(context.is_var("a"));
+    /// ```
+    pub fn clear_functions(&mut self) {
+        self.functions.clear();
+    }
+
+    /// Removes all the user-defined variables.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("f(x) = 2x", &mut context).unwrap();
+    /// eval_with_mutable_context("a = 2", &mut context).unwrap();
+    ///
+    /// context.clear_variables();
+    ///
+    /// assert!(context.is_function("f"));
+    /// assert!(!context.is_var("a"));
+    /// ```
+    pub fn clear_variables(&mut self) {
+        self.variables.clear();
+    }
+
+    /// Returns an iterator over the identifiers of the user-defined functions, in
+    /// arbitrary order.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("f(x) = 2x", &mut context).unwrap();
+    /// eval_with_mutable_context("g(x) = 3x", &mut context).unwrap();
+    ///
+    /// let mut names = context.function_names().collect::<Vec<&String>>();
+    /// names.sort();
+    /// assert_eq!(names, vec!["f", "g"]);
+    /// ```
+    pub fn function_names(&self) -> impl Iterator<Item = &String> {
+        self.functions.keys()
+    }
+
+    /// Returns an iterator over the identifiers of the user-defined variables, in
+    /// arbitrary order.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("a = 2", &mut context).unwrap();
+    ///
+    /// assert_eq!(context.variable_names().collect::<Vec<&String>>(), vec!["a"]);
+    /// ```
+    pub fn variable_names(&self) -> impl Iterator<Item = &String> {
+        self.variables.keys()
+    }
+
+    /// Returns an iterator over the user-defined functions, with their identifiers,
+    /// parameters and bodies, in arbitrary order.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("f(x, y) = x + y", &mut context).unwrap();
+    ///
+    /// for (identifier, (params, _body)) in context.iter_functions() {
+    ///     assert_eq!(identifier, "f");
+    ///     assert_eq!(params, &vec!["x", "y"]);
+    /// }
+    /// ```
+    pub fn iter_functions(
+        &self,
+    ) -> impl Iterator<Item = (&String, &(Vec<String>, Box<Expression>))> {
+        self.functions.iter()
+    }
+
     /// Returns a user-defined function given an identifier.
     pub fn get_function(&self, identifier: &str) -> Option<(Vec<String>, Box<Expression>)> {
         self.functions.get(identifier).cloned()
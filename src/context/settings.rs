@@ -2,12 +2,13 @@
 //! Contains contexts settings.
 //!
 
-use std::default::Default;
-use std::f64::consts;
+use core::default::Default;
+use core::f64::consts;
 
-use crate::{EvalResult, Value};
+use crate::{ErrorType, EvalResult, prelude::*, Value, value::valuetype::ValueType};
 
-/// The number of decimal places shown.
+/// The number of decimal places shown. Rational values are exact, so they are
+/// not rounded.
 ///
 /// ## Examples
 /// ```
@@ -25,6 +26,72 @@ use crate::{EvalResult, Value};
 pub enum Rounding {
     /// Round number to a specific decimal place.
     Round(u8),
+    /// Round number to a specific decimal place, rounding halfway cases
+    /// to the nearest even digit (banker's rounding).
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::RoundHalfEven(0),
+    ///     settings::AngleUnit::default(),
+    ///     settings::DepthLimit::default()
+    /// );
+    ///
+    /// assert_eq!(eval_with_static_context("2.5", &context).unwrap(), Value::from(2));
+    /// assert_eq!(eval_with_static_context("3.5", &context).unwrap(), Value::from(4));
+    /// ```
+    RoundHalfEven(u8),
+    /// Round number towards negative infinity at a specific decimal place.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::Floor(2),
+    ///     settings::AngleUnit::default(),
+    ///     settings::DepthLimit::default()
+    /// );
+    ///
+    /// assert_eq!(eval_with_static_context("1.2349", &context).unwrap(), Value::from(1.23));
+    /// assert_eq!(eval_with_static_context("-1.2349", &context).unwrap(), Value::from(-1.24));
+    /// assert_eq!(eval_with_static_context("1.230", &context).unwrap(), Value::from(1.23));
+    /// assert_eq!(eval_with_static_context("1.13", &context).unwrap(), Value::from(1.13));
+    /// ```
+    Floor(u8),
+    /// Round number towards positive infinity at a specific decimal place.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::Ceil(2),
+    ///     settings::AngleUnit::default(),
+    ///     settings::DepthLimit::default()
+    /// );
+    ///
+    /// assert_eq!(eval_with_static_context("1.2349", &context).unwrap(), Value::from(1.24));
+    /// assert_eq!(eval_with_static_context("-1.2349", &context).unwrap(), Value::from(-1.23));
+    /// assert_eq!(eval_with_static_context("1.230", &context).unwrap(), Value::from(1.23));
+    /// assert_eq!(eval_with_static_context("-1.13", &context).unwrap(), Value::from(-1.13));
+    /// ```
+    Ceil(u8),
+    /// Round number to a specific amount of significant figures.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::SignificantFigures(3),
+    ///     settings::AngleUnit::default(),
+    ///     settings::DepthLimit::default()
+    /// );
+    ///
+    /// assert_eq!(eval_with_static_context("0.00123456", &context).unwrap(), Value::from(0.00123));
+    /// assert_eq!(eval_with_static_context("123456", &context).unwrap(), Value::from(123000));
+    /// assert_eq!(eval_with_static_context("0", &context).unwrap(), Value::from(0));
+    /// ```
+    SignificantFigures(u8),
     /// Disable rounding.
     NoRounding,
 }
@@ -35,7 +102,65 @@ impl Default for Rounding {
     }
 }
 
-impl Rounding {}
+impl Rounding {
+    /// Rounds a float according to the rounding mode.
+    pub fn apply(self, value: f64) -> f64 {
+        match self {
+            Rounding::Round(precision) => {
+                let factor = Self::factor(precision);
+                (value * factor).round() / factor
+            }
+            Rounding::RoundHalfEven(precision) => {
+                let factor = Self::factor(precision);
+                #[cfg(feature = "std")]
+                let rounded = (value * factor).round_ties_even();
+                #[cfg(not(feature = "std"))]
+                let rounded = libm::rint(value * factor);
+                rounded / factor
+            }
+            Rounding::Floor(precision) => {
+                let factor = Self::factor(precision);
+                Self::snap(value * factor).floor() / factor
+            }
+            Rounding::Ceil(precision) => {
+                let factor = Self::factor(precision);
+                Self::snap(value * factor).ceil() / factor
+            }
+            Rounding::SignificantFigures(figures) => {
+                if value == 0.0 || !value.is_finite() {
+                    return value;
+                }
+                let exponent = value.abs().log10().floor() as i32;
+                let decimals = figures.max(1) as i32 - 1 - exponent;
+                if decimals >= 0 {
+                    let factor = 10.0f64.powi(decimals);
+                    (value * factor).round() / factor
+                } else {
+                    let factor = 10.0f64.powi(-decimals);
+                    (value / factor).round() * factor
+                }
+            }
+            Rounding::NoRounding => value,
+        }
+    }
+
+    /// Snaps a scaled value to the closest integer if they only differ by a
+    /// floating point error, so that values such as `1.13 * 100` are not
+    /// floored to `112`.
+    fn snap(scaled: f64) -> f64 {
+        let nearest = scaled.round();
+        if (scaled - nearest).abs() <= 4.0 * f64::EPSILON * scaled.abs().max(1.0) {
+            nearest
+        } else {
+            scaled
+        }
+    }
+
+    /// Returns the scaling factor for a decimal place.
+    fn factor(precision: u8) -> f64 {
+        10.0f64.powi(precision.clamp(0, 12) as i32)
+    }
+}
 
 /// The angle unit to use.
 ///
@@ -45,7 +170,7 @@ impl Rounding {}
 ///
 /// let my_context = Context::new(
 ///     settings::Rounding::default(),
-///     settings::AngleUnit::Degree, // Or Radian or Turn
+///     settings::AngleUnit::Degree, // Or Radian, Turn or Gradian
 ///     settings::DepthLimit::default()
 /// );
 ///
@@ -60,74 +185,53 @@ pub enum AngleUnit {
     Degree,
     /// Measure angles in turns. A full turn is 1.
     Turn,
+    /// Measure angles in gradians. A full turn is 400.
+    Gradian,
 }
 
 impl AngleUnit {
     /// Returns the angle unit default value.
-    /// Converts a value from an angle unit to another.
+    /// Converts a value from an angle unit to another. A turn is `2π` radians: earlier
+    /// versions divided turns by `π` instead of multiplying them, so that a quarter
+    /// turn gave about `0.16` radians.
+    ///
+    /// ## Examples
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let gradians = settings::AngleUnit::Degree
+    ///     .convert_value(settings::AngleUnit::Gradian, Value::from(90))
+    ///     .unwrap();
+    /// assert_eq!(gradians.as_float().unwrap().round(), 100.0);
+    ///
+    /// let degrees = settings::AngleUnit::Gradian
+    ///     .convert_value(settings::AngleUnit::Degree, gradians)
+    ///     .unwrap();
+    /// assert!((degrees.as_float().unwrap() - 90.0).abs() < 1e-12);
+    ///
+    /// let radians = settings::AngleUnit::Turn
+    ///     .convert_value(settings::AngleUnit::Radian, Value::from(0.25))
+    ///     .unwrap();
+    /// assert!((radians.as_float().unwrap() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+    ///
+    /// let turns = settings::AngleUnit::Radian
+    ///     .convert_value(settings::AngleUnit::Turn, radians)
+    ///     .unwrap();
+    /// assert!((turns.as_float().unwrap() - 0.25).abs() < 1e-12);
+    /// ```
     pub fn convert_value(self, to: Self, value: Value) -> EvalResult<Value> {
         let as_radians = match self {
             Self::Radian => value,
-            Self::Degree => {
-                let division_result = value / Value::from(180);
-                let division_value = match division_result {
-                    Ok(val) => val,
-                    Err(e) => return Err(e), // Return the error if division fails
-                };
-                let final_result = division_value * Value::from(consts::PI);
-                let result_value = match final_result {
-                    Ok(val) => val,
-                    Err(e) => return Err(e), // Return the error if multiplication fails
-                };
-                result_value
-            }
-            Self::Turn => {
-                let division_result = value / Value::from(0.5);
-                let division_value = match division_result {
-                    Ok(val) => val,
-                    Err(e) => return Err(e), // Return the error if division fails
-                };
-
-                let final_result = division_value / Value::from(consts::PI);
-                let result_value = match final_result {
-                    Ok(val) => val,
-                    Err(e) => return Err(e), // Return the error if multiplication fails
-                };
-
-                result_value
-            }
+            Self::Degree => ((value / Value::from(180))? * Value::from(consts::PI))?,
+            Self::Turn => ((value / Value::from(0.5))? * Value::from(consts::PI))?,
+            Self::Gradian => ((value / Value::from(200))? * Value::from(consts::PI))?,
         };
 
         Ok(match to {
             Self::Radian => as_radians,
-            Self::Degree => {
-                // Perform division and multiplication for Degree
-                let division_result = as_radians / Value::from(consts::PI);
-                let division_value = match division_result {
-                    Ok(val) => val,
-                    Err(e) => return Err(e), // Return error if division fails
-                };
-
-                let final_result = division_value * Value::from(180);
-                match final_result {
-                    Ok(val) => val,
-                    Err(e) => return Err(e), // Return error if multiplication fails
-                }
-            }
-            Self::Turn => {
-                // Perform division and multiplication for Turn
-                let division_result = as_radians / Value::from(consts::PI);
-                let division_value = match division_result {
-                    Ok(val) => val,
-                    Err(e) => return Err(e), // Return error if division fails
-                };
-
-                let final_result = division_value * Value::from(0.5);
-                match final_result {
-                    Ok(val) => val,
-                    Err(e) => return Err(e), // Return error if multiplication fails
-                }
-            }
+            Self::Degree => ((as_radians / Value::from(consts::PI))? * Value::from(180))?,
+            Self::Turn => ((as_radians / Value::from(consts::PI))? * Value::from(0.5))?,
+            Self::Gradian => ((as_radians / Value::from(consts::PI))? * Value::from(200))?,
         })
     }
 }
@@ -162,3 +266,152 @@ impl Default for DepthLimit {
         DepthLimit::Limit(49)
     }
 }
+
+/// The maximum amount of iterations of functions like `sum` and `product`.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// context.iteration_limit = settings::IterationLimit::Limit(10);
+///
+/// assert!(eval_with_static_context("sum(k, 1, 10, k)", &context).is_ok());
+/// assert!(eval_with_static_context("sum(k, 1, 11, k)", &context).is_err());
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IterationLimit {
+    /// An iteration limit.
+    Limit(u32),
+    /// No limit.
+    NoLimit,
+}
+
+impl Default for IterationLimit {
+    fn default() -> Self {
+        IterationLimit::Limit(100_000)
+    }
+}
+
+/// The base used to display integer results.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// context.output_base = settings::OutputBase::Hex;
+///
+/// let value = eval_with_static_context("255", &context).unwrap();
+///
+/// assert_eq!(context.output_base.format(&value).unwrap(), "0xff");
+/// assert_eq!(settings::OutputBase::Binary.format(&Value::from(-5)).unwrap(), "-0b101");
+/// assert_eq!(settings::OutputBase::Octal.format(&Value::from(8.0)).unwrap(), "0o10");
+/// assert!(settings::OutputBase::Hex.format(&Value::from(1.5)).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutputBase {
+    #[default]
+    /// Display numbers in base 10.
+    Decimal,
+    /// Display integers in base 16, prefixed by `0x`.
+    Hex,
+    /// Display integers in base 2, prefixed by `0b`.
+    Binary,
+    /// Display integers in base 8, prefixed by `0o`.
+    Octal,
+}
+
+impl OutputBase {
+    /// Formats a value in the output base. Vectors are formatted element by element.
+    ///
+    /// Any value is accepted in `Decimal` mode, while other bases return an error for
+    /// values which are not integers.
+    pub fn format(self, value: &Value) -> EvalResult<String> {
+        match value {
+            Value::Vector(vec) => {
+                let mut as_string = vec![];
+
+                for elem in vec {
+                    as_string.push(self.format(elem)?);
+                }
+
+                Ok(format!("[{}]", as_string.join(", ")))
+            }
+            _ if self == Self::Decimal => Ok(value.to_string()),
+            _ if !value.is_bool() && value.is_integer() => {
+                let int = value.as_int()?;
+                let sign = if int < 0 { "-" } else { "" };
+                let abs = int.unsigned_abs();
+
+                Ok(match self {
+                    Self::Hex => format!("{}0x{:x}", sign, abs),
+                    Self::Binary => format!("{}0b{:b}", sign, abs),
+                    Self::Octal => format!("{}0o{:o}", sign, abs),
+                    Self::Decimal => unreachable!(),
+                })
+            }
+            _ => Err(ErrorType::TypeError {
+                expected: ValueType::IntType,
+                given: value.get_type(),
+            }),
+        }
+    }
+}
+
+/// The separator used to group the digits of the integer part of displayed numbers
+/// by thousands. Grouping only affects formatting, not parsing or values.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// assert_eq!(settings::GroupDigits(Some(',')).apply("1000000"), "1,000,000");
+/// assert_eq!(settings::GroupDigits(Some(' ')).apply("-12345.6789"), "-12 345.6789");
+/// assert_eq!(settings::GroupDigits(None).apply("1000000"), "1000000");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupDigits(pub Option<char>);
+
+impl GroupDigits {
+    /// Inserts the separator in every integer part of a formatted string.
+    pub fn apply(self, formatted: &str) -> String {
+        let separator = match self.0 {
+            Some(separator) => separator,
+            None => return formatted.to_string(),
+        };
+
+        let mut output = String::new();
+        let mut digits = String::new();
+        let mut is_fraction = false;
+
+        for c in formatted.chars().chain(core::iter::once('\0')) {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+
+            if is_fraction {
+                output.push_str(&digits);
+            } else {
+                for (index, digit) in digits.chars().enumerate() {
+                    if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                        output.push(separator);
+                    }
+                    output.push(digit);
+                }
+            }
+            digits.clear();
+
+            is_fraction = c == '.';
+            if c != '\0' {
+                output.push(c);
+            }
+        }
+
+        output
+    }
+}
@@ -2,22 +2,21 @@
 //! Contains functions to easily retrieve and set built-in functions and constants.
 //!
 
-use std::collections::HashMap;
-use std::f64::*;
-use std::sync::{LazyLock, RwLock};
+use core::cmp::Ordering;
+use core::f64::*;
 
 use crate::{
-    create_func, decl_func, function::Function, function::*, out::ErrorType, read_vec_values,
-    value::Value, EvalResult, ValueType,
+    create_func, decl_func, function::Function, function::*, out::ErrorType, prelude::*, read_vec_values,
+    value::{IntValue, RationalValue, Value}, EvalResult, ValueType,
 };
-use num::complex::ComplexFloat;
+use num::Integer;
 use rand::Rng;
-use tuple_conv::RepeatedTuple;
 
 static CONSTANTS: LazyLock<RwLock<HashMap<&'static str, Value>>> = LazyLock::new(|| {
     let mut m = HashMap::new();
     // Math constants
     m.insert("pi", Value::Float(consts::PI));
+    m.insert("π", Value::Float(consts::PI));
     m.insert("e", Value::Float(consts::E));
     m.insert("tau", Value::Float(consts::TAU));
     m.insert("phi", Value::Float(1.618_033_988_749_894));
@@ -32,24 +31,63 @@ static CONSTANTS: LazyLock<RwLock<HashMap<&'static str, Value>>> = LazyLock::new
 
 static BUILT_IN_FUNCTIONS: LazyLock<RwLock<Vec<Function>>> = LazyLock::new(|| {
     let m = vec![
+        create_func!(len, Arguments::Dynamic),
         create_func!(min, Arguments::Dynamic),
         create_func!(max, Arguments::Dynamic),
+        create_func!(clamp, Arguments::Const(3)),
+        create_func!(mean, Arguments::Dynamic),
+        create_func!(median, Arguments::Dynamic),
+        create_func!(stddev, Arguments::Dynamic),
         create_func!(floor, Arguments::Const(1)),
         create_func!(ceil, Arguments::Const(1)),
         create_func!(round, Arguments::Const(1)),
+        create_func!(trunc, Arguments::Const(1)),
+        create_func!(sign, Arguments::Const(1)),
         create_func!(abs, Arguments::Const(1)),
         create_func!(sqrt, Arguments::Const(1)),
+        create_func!(gamma, Arguments::Const(1)),
+        create_func!(cbrt, Arguments::Const(1)),
+        create_func!(root, Arguments::Const(2)),
+        create_func!(hypot, Arguments::Dynamic),
         create_func!(ln, Arguments::Const(1)),
-        create_func!(log, Arguments::Const(2)),
+        create_func!(log, Arguments::Dynamic),
         create_func!(exp, Arguments::Const(1)),
+        create_func!(erf, Arguments::Const(1)),
+        create_func!(erfc, Arguments::Const(1)),
         create_func!(rand, Arguments::Const(2)),
+        create_func!(random, Arguments::Const(0)),
+        create_func!(randint, Arguments::Const(2)),
+        create_func!(gcd, Arguments::Dynamic),
+        create_func!(lcm, Arguments::Dynamic),
+        create_func!(factorial, Arguments::Const(1)),
+        create_func!(doublefactorial, Arguments::Const(1)),
+        create_func!(nCr, Arguments::Const(2)),
+        create_func!(nPr, Arguments::Const(2)),
+        create_func!(isprime, Arguments::Const(1)),
+        create_func!(nextprime, Arguments::Const(1)),
+        create_func!(factorize, Arguments::Const(1)),
+        create_func!(tobase, Arguments::Const(2)),
+        create_func!(frombase, Arguments::Const(2)),
+        create_func!(xor, Arguments::Const(2)),
         create_func!(branch, Arguments::Const(3)),
+        Function::new("if", branch, Arguments::Const(3)),
+        create_func!(sum, Arguments::Const(4)),
+        create_func!(product, Arguments::Const(4)),
+        create_func!(derivative, Arguments::Const(2)),
+        create_func!(solve, Arguments::Const(3)),
+        create_func!(integrate, Arguments::Const(3)),
+        create_func!(map, Arguments::Const(2)),
+        create_func!(filter, Arguments::Const(2)),
+        create_func!(reduce, Arguments::Const(3)),
         create_func!(sin, Arguments::Const(1)),
         create_func!(cos, Arguments::Const(1)),
         create_func!(tan, Arguments::Const(1)),
         create_func!(asin, Arguments::Const(1)),
         create_func!(acos, Arguments::Const(1)),
         create_func!(atan, Arguments::Const(1)),
+        create_func!(atan2, Arguments::Const(2)),
+        create_func!(degrees, Arguments::Const(1)),
+        create_func!(radians, Arguments::Const(1)),
         create_func!(sinh, Arguments::Const(1)),
         create_func!(cosh, Arguments::Const(1)),
         create_func!(tanh, Arguments::Const(1)),
@@ -60,7 +98,13 @@ static BUILT_IN_FUNCTIONS: LazyLock<RwLock<Vec<Function>>> = LazyLock::new(|| {
         create_func!(im, Arguments::Const(1)),
         create_func!(polar, Arguments::Const(1)),
         create_func!(arg, Arguments::Const(1)),
-        create_func!(norm, Arguments::Const(1)),
+        create_func!(norm, Arguments::Dynamic),
+        create_func!(dot, Arguments::Const(2)),
+        create_func!(cross, Arguments::Const(2)),
+        create_func!(matmul, Arguments::Const(2)),
+        create_func!(transpose, Arguments::Dynamic),
+        create_func!(det, Arguments::Dynamic),
+        create_func!(solve_linear, Arguments::Const(2)),
     ];
 
     RwLock::new(m)
@@ -97,6 +141,27 @@ pub fn reserved_keywords<'a>() -> Vec<&'a str> {
     .concat()
 }
 
+/// Returns the built-in functions whose first argument is not evaluated, as it is
+/// either a variable they bind, like in `sum`, or a function, like in `derivative`.
+pub fn binding_functions<'a>() -> Vec<&'a str> {
+    vec![
+        "sum",
+        "product",
+        "derivative",
+        "solve",
+        "integrate",
+        "map",
+        "filter",
+        "reduce",
+    ]
+}
+
+/// Returns the built-in functions which do not evaluate all of their arguments, or
+/// evaluate them in a different scope.
+pub fn lazy_functions<'a>() -> Vec<&'a str> {
+    [binding_functions(), vec!["branch", "if"]].concat()
+}
+
 /// Get a cloned vector of all built-in functions.
 pub fn get_built_in_functions_vec() -> Vec<Function> {
     BUILT_IN_FUNCTIONS.read().unwrap().iter().cloned().collect()
@@ -148,72 +213,393 @@ pub fn remove_built_in_const(const_identifier: &str) -> Option<Value> {
 // STD
 
 decl_func!(
+    /// Returns the number of elements of a list. Since a single list argument is
+    /// spread into the function arguments, it also returns the number of arguments.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_script("v = [4, 5, 6]", &mut context).unwrap();
+    ///
+    /// assert_eq!(eval_with_static_context("len(v)", &context).unwrap(), Value::from(3));
+    /// assert_eq!(eval("len([1, 2])").unwrap(), Value::from(2));
+    /// assert_eq!(eval("len([[1, 2], [3, 4, 5]])").unwrap(), Value::from(2));
+    /// assert_eq!(eval("len([])").unwrap(), Value::from(0));
+    /// assert_eq!(eval("len(7)").unwrap(), Value::from(1));
+    /// ```
+    len,
+    FunctionType::Std,
+    |v: Value| Ok(Value::Int(v.as_vector().len() as IntValue)),
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Returns the minimum of the values. If any of them is `NaN`, it is returned.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("min(1.5, 1.2)").unwrap(), Value::from(1.2));
+    /// assert_eq!(eval("min(3, 1/2, 7)").unwrap(), eval("1/2").unwrap());
+    /// assert!(eval("min(1, i)").is_err());
+    /// assert!(eval("min(1, x)").is_err());
+    /// assert!(matches!(eval("min([])"), Err(ErrorType::EmptyUnion)));
+    /// ```
     min,
     FunctionType::Std,
-    |v| {
-        let vec = v.as_vector();
-        let mut min = vec[0].as_float()?;
-        for elem in vec {
-            if elem.as_float()? < min {
-                min = elem.as_float()?;
-            }
-        }
-        Ok(Value::Float(min))
-    },
+    |v| extremum(v, Ordering::Less),
     ValueType::VectorType
 );
 
 decl_func!(
+    /// Returns the maximum of the values. If any of them is `NaN`, it is returned.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("max(3, 7, 2)").unwrap(), Value::from(7));
+    /// assert_eq!(eval("max(-1, -2.5)").unwrap(), Value::from(-1));
+    /// assert!(eval("max(1, 2i)").is_err());
+    /// assert!(matches!(eval("max([])"), Err(ErrorType::EmptyUnion)));
+    /// ```
     max,
     FunctionType::Std,
+    |v| extremum(v, Ordering::Greater),
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Restricts a number to the range between the lower and the upper bound. The
+    /// lower bound can not be greater than the upper one.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("clamp(5, 1, 3)").unwrap(), Value::from(3));
+    /// assert_eq!(eval("clamp(-5, 1, 3)").unwrap(), Value::from(1));
+    /// assert_eq!(eval("clamp(2.5, 1, 3)").unwrap(), Value::from(2.5));
+    /// assert_eq!(eval("clamp(1, 1, 3)").unwrap(), Value::from(1));
+    /// assert_eq!(eval("clamp(3, 1, 3)").unwrap(), Value::from(3));
+    /// assert_eq!(eval("clamp(3, 3, 3)").unwrap(), Value::from(3));
+    /// assert!(eval("clamp(2, 3, 1)").is_err());
+    /// ```
+    clamp,
+    FunctionType::Std,
     |v| {
-        let vec = v.as_vector();
-        let mut max = vec[0].as_float()?;
-        for elem in vec {
-            if elem.as_float()? > max {
-                max = elem.as_float()?;
-            }
+        read_vec_values!(v, x, lower, upper);
+        let result = extremum(
+            Value::Vector(vec![
+                extremum(Value::Vector(vec![x.clone(), lower.clone()]), Ordering::Greater)?,
+                upper.clone(),
+            ]),
+            Ordering::Less,
+        )?;
+
+        if lower > upper {
+            return Err(ErrorType::DomainError {
+                value: lower.clone(),
+                operation_name: "Clamp",
+            });
         }
-        Ok(Value::Float(max))
+        Ok(result)
     },
     ValueType::VectorType
 );
 
+/// Returns the value which compares as `ordering` with all the others, or the
+/// first `NaN` found. Values which are not real numbers return a `TypeError`, and
+/// an empty list returns `ErrorType::EmptyUnion`.
+fn extremum(values: Value, ordering: Ordering) -> EvalResult<Value> {
+    let values = values.as_vector();
+    let mut result = values.first().ok_or(ErrorType::EmptyUnion)?.clone();
+
+    for value in values {
+        match value.as_f64() {
+            Some(number) if number.is_nan() => return Ok(value),
+            Some(_) => (),
+            None => {
+                return Err(ErrorType::TypeError {
+                    expected: ValueType::FloatType,
+                    given: value.to_type(),
+                })
+            }
+        }
+        if value.partial_cmp(&result) == Some(ordering) {
+            result = value;
+        }
+    }
+
+    Ok(result)
+}
+
 decl_func!(
+    /// Returns the greatest integer lower than or equal to the number.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("floor(2.7)").unwrap(), Value::Int(2));
+    /// assert_eq!(eval("floor(-2.7)").unwrap(), Value::Int(-3));
+    /// assert_eq!(eval("floor(7/2)").unwrap(), Value::Int(3));
+    /// assert_eq!(eval("floor(9/2)*2").unwrap(), Value::Int(8));
+    /// ```
     floor,
     FunctionType::Std,
-    |v| Ok(v.as_float()?.floor()),
-    ValueType::FloatType
+    |v| {
+        read_vec_values!(v, x);
+        to_integer(x, f64::floor, RationalValue::floor)
+    },
+    ValueType::VectorType
 );
 
 decl_func!(
+    /// Returns the lowest integer greater than or equal to the number.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("ceil(2.1)").unwrap(), Value::Int(3));
+    /// assert_eq!(eval("ceil(-2.1)").unwrap(), Value::Int(-2));
+    /// assert_eq!(eval("ceil(4)").unwrap(), Value::Int(4));
+    /// ```
     ceil,
     FunctionType::Std,
-    |v| Ok(v.as_float()?.ceil()),
-    ValueType::FloatType
+    |v| {
+        read_vec_values!(v, x);
+        to_integer(x, f64::ceil, RationalValue::ceil)
+    },
+    ValueType::VectorType
 );
 
 decl_func!(
+    /// Returns the nearest integer, rounding half-way cases away from zero.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("round(2.5)").unwrap(), Value::Int(3));
+    /// assert_eq!(eval("round(-2.5)").unwrap(), Value::Int(-3));
+    /// assert_eq!(eval("round(2.4)").unwrap(), Value::Int(2));
+    /// assert_eq!(eval("round(1/2)").unwrap(), Value::Int(1));
+    /// ```
     round,
     FunctionType::Std,
-    |v| Ok(v.as_float()?.round()),
-    ValueType::FloatType
+    |v| {
+        read_vec_values!(v, x);
+        to_integer(x, f64::round, RationalValue::round)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Returns the integer part of the number, rounding towards zero.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("trunc(2.7)").unwrap(), Value::Int(2));
+    /// assert_eq!(eval("trunc(-2.7)").unwrap(), Value::Int(-2));
+    /// assert_eq!(eval("trunc(-7/2)").unwrap(), Value::Int(-3));
+    /// assert!(eval("trunc(2+i)").is_err());
+    /// ```
+    trunc,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x);
+        to_integer(x, f64::trunc, RationalValue::trunc)
+    },
+    ValueType::VectorType
 );
 
+decl_func!(
+    /// Returns `-1`, `0` or `1` if the number is respectively negative, zero or
+    /// positive. Negative zero is zero, while `NaN` returns a `DomainError`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("sign(-3.5)").unwrap(), Value::Int(-1));
+    /// assert_eq!(eval("sign(0)").unwrap(), Value::Int(0));
+    /// assert_eq!(eval("sign(-0.0)").unwrap(), Value::Int(0));
+    /// assert_eq!(eval("sign(2/3)").unwrap(), Value::Int(1));
+    /// assert!(eval("sign(i)").is_err());
+    /// ```
+    sign,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x);
+        match x {
+            Value::Int(_) | Value::Bool(_) => Ok(Value::Int(x.as_int()?.signum())),
+            Value::Rational(r) => Ok(Value::Int(r.numer().signum())),
+            other => {
+                let number = other.as_float()?;
+                if number.is_nan() {
+                    Err(ErrorType::DomainError {
+                        value: other.clone(),
+                        operation_name: "Sign",
+                    })
+                } else if number == 0.0 {
+                    Ok(Value::Int(0))
+                } else {
+                    Ok(Value::Int(number.signum() as IntValue))
+                }
+            }
+        }
+    },
+    ValueType::VectorType
+);
+
+/// Rounds a real number to an integer using the provided functions. Floats
+/// which do not fit into an `IntValue` are kept as such.
+fn to_integer(
+    value: &Value,
+    float_operation: fn(f64) -> f64,
+    exact_operation: fn(&RationalValue) -> RationalValue,
+) -> EvalResult<Value> {
+    match value {
+        Value::Int(_) | Value::Bool(_) => Ok(Value::Int(value.as_int()?)),
+        Value::Rational(r) => Ok(Value::from(exact_operation(r))),
+        other => {
+            let rounded = float_operation(other.as_float()?);
+            Ok(Value::Float(rounded).try_as_type(ValueType::IntType))
+        }
+    }
+}
+
 decl_func!(
     abs,
     FunctionType::Std,
-    |v: Value| Ok(Value::Float(v.as_complex()?.abs())),
+    |v: Value| Ok(Value::Float(v.as_complex()?.norm())),
     ValueType::ComplexType
 );
 
+decl_func!(
+    /// Returns the gamma function of a real number, which extends the factorial as
+    /// `gamma(n) = (n - 1)!`. It is not defined at non-positive integers.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("gamma(5)").unwrap(), Value::from(24));
+    /// assert_eq!(eval("gamma(1)").unwrap(), Value::from(1));
+    ///
+    /// let close_to = |expression: &str, expected: f64| {
+    ///     let value = eval(expression).unwrap().as_float().unwrap();
+    ///     (value - expected).abs() < 1e-7
+    /// };
+    /// assert!(close_to("gamma(0.5)", std::f64::consts::PI.sqrt()));
+    /// assert!(close_to("gamma(-0.5)", -2.0 * std::f64::consts::PI.sqrt()));
+    /// assert!(close_to("gamma(4.5)", 11.631728396567448));
+    /// assert!(close_to("gamma(0.1)", 9.513507698668732));
+    ///
+    /// assert!(matches!(eval("gamma(0)"), Err(ErrorType::DomainError { .. })));
+    /// assert!(matches!(eval("gamma(-3)"), Err(ErrorType::DomainError { .. })));
+    /// ```
+    gamma,
+    FunctionType::Std,
+    |v: Value| v.gamma(),
+    ValueType::FloatType
+);
+
 decl_func!(
     sqrt,
     FunctionType::Std,
-    |v: Value| Ok(Value::Complex(v.as_complex()?.sqrt())),
+    |v: Value| v.sqrt(),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the real cube root of a real number, or the principal one of a
+    /// complex number. Note that `(-27)^(1/3)` is the principal root instead.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("cbrt(27)").unwrap(), Value::from(3));
+    /// assert_eq!(eval("cbrt(-27)").unwrap(), Value::from(-3));
+    /// assert!(eval("(-27)^(1/3)").unwrap().is_complex());
+    /// assert_eq!(eval("cbrt(-8)").unwrap(), Value::from(-2));
+    /// assert_eq!(eval("cbrt(0.001)").unwrap(), Value::from(0.1));
+    /// ```
+    cbrt,
+    FunctionType::Std,
+    |v: Value| match v.as_f64() {
+        Some(x) => Ok(Value::Float(x.cbrt())),
+        None => Ok(Value::Complex(v.as_complex()?.powf(1.0 / 3.0))),
+    },
     ValueType::ComplexType
 );
 
+decl_func!(
+    /// Returns the nth root of a number. Odd integer roots of negative numbers are
+    /// real, while other roots of negative numbers are the principal complex ones.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("root(27, 3)").unwrap(), Value::from(3));
+    /// assert_eq!(eval("root(16, 4)").unwrap(), Value::from(2));
+    /// assert_eq!(eval("root(-32, 5)").unwrap(), Value::from(-2));
+    /// assert_eq!(eval("root(4, -2)").unwrap(), Value::from(0.5));
+    /// assert_eq!(eval("root(-4, 2)").unwrap(), eval("2i").unwrap());
+    /// assert!(eval("root(4, 0)").is_err());
+    /// ```
+    root,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, n);
+        let degree = n.as_float()?;
+        if degree == 0.0 {
+            return Err(ErrorType::DomainError {
+                value: n.clone(),
+                operation_name: "Root",
+            });
+        }
+
+        let is_odd = n.is_integer() && degree % 2.0 != 0.0;
+        match x.as_f64() {
+            Some(radicand) if radicand >= 0.0 || is_odd => {
+                let root = radicand.abs().powf(1.0 / degree).copysign(radicand);
+                // Return exact results for integer roots
+                if root.round().powf(degree) == radicand {
+                    Ok(Value::Float(root.round()))
+                } else {
+                    Ok(Value::Float(root))
+                }
+            }
+            _ => Ok(Value::Complex(x.as_complex()?.powf(1.0 / degree))),
+        }
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Returns the square root of the sum of the squares of the numbers, avoiding
+    /// intermediate overflows.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("hypot(3, 4)").unwrap(), Value::from(5));
+    /// assert_eq!(eval("hypot(2, 3, 6)").unwrap(), Value::from(7));
+    /// assert_eq!(eval("hypot(-5)").unwrap(), Value::from(5));
+    ///
+    /// let large = eval("hypot(3e200, 4e200)").unwrap().as_float().unwrap();
+    /// assert!((large / 5e200 - 1.0).abs() < 1e-15);
+    /// ```
+    hypot,
+    FunctionType::Std,
+    |v| {
+        let mut result: f64 = 0.0;
+        for elem in v.as_vector() {
+            result = result.hypot(elem.as_float()?);
+        }
+        Ok(Value::Float(result))
+    },
+    ValueType::VectorType
+);
+
 decl_func!(
     ln,
     FunctionType::Std,
@@ -222,11 +608,74 @@ decl_func!(
 );
 
 decl_func!(
+    /// Returns the logarithm of a number with the specified base, which is 10 if
+    /// omitted. The base must be positive and can not be 1. Real numbers must be
+    /// positive, while complex ones have their principal logarithm.
+    ///
+    /// The number comes first: earlier versions took the base as the first argument,
+    /// as in `log(2, 8)`, which is now written `log(8, 2)`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("log(8, 2)").unwrap(), Value::from(3));
+    /// assert_eq!(eval("log(1000, 10)").unwrap(), Value::from(3));
+    /// assert_eq!(eval("log(1000)").unwrap(), Value::from(3));
+    /// assert_eq!(eval("log(1/9, 3)").unwrap(), Value::from(-2));
+    ///
+    /// let z = eval("log(100i, 10)").unwrap().as_complex().unwrap();
+    /// assert!((z.re - 2.0).abs() < 1e-8);
+    /// assert!((z.im - 0.682188176920921).abs() < 1e-8);
+    ///
+    /// assert!(eval("log(0, 2)").is_err());
+    /// assert!(eval("log(-8, 2)").is_err());
+    /// assert!(eval("log(8, 1)").is_err());
+    /// assert!(eval("log(8, -2)").is_err());
+    /// ```
     log,
     FunctionType::Std,
     |v| {
-        read_vec_values!(v, base, argument);
-        Ok(argument.as_complex()?.log(base.as_float()?))
+        let (x, base) = match &v.as_vector()[..] {
+            [x] => (x.clone(), Value::Int(10)),
+            [x, base] => (x.clone(), base.clone()),
+            other => {
+                return Err(ErrorType::WrongFunctionArgumentsAmount {
+                    func_name: String::from("log"),
+                    expected: 2,
+                    given: other.len() as u8,
+                })
+            }
+        };
+        let base_float = base.as_float()?;
+        if base_float <= 0.0 || base_float == 1.0 {
+            return Err(ErrorType::DomainError {
+                value: base,
+                operation_name: "Logarithm",
+            });
+        }
+
+        let x_float = match x.as_f64() {
+            Some(x_float) => x_float,
+            None => return Ok(Value::Complex(x.as_complex()?.log(base_float))),
+        };
+        if x_float <= 0.0 {
+            return Err(ErrorType::DomainError {
+                value: x,
+                operation_name: "Logarithm",
+            });
+        }
+
+        let result = match base_float {
+            2.0 => x_float.log2(),
+            10.0 => x_float.log10(),
+            _ => x_float.ln() / base_float.ln(),
+        };
+        // Return exact results for integer powers of the base
+        if base_float.powf(result.round()) == x_float {
+            Ok(Value::Float(result.round()))
+        } else {
+            Ok(Value::Float(result))
+        }
     },
     ValueType::VectorType
 );
@@ -239,148 +688,1955 @@ decl_func!(
 );
 
 decl_func!(
-    rand,
+    /// Returns the error function of a real number, with an absolute error below
+    /// `1.5e-7`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let close_to = |expression: &str, expected: f64| {
+    ///     (eval(expression).unwrap().as_float().unwrap() - expected).abs() < 2e-7
+    /// };
+    /// assert_eq!(eval("erf(0)").unwrap(), Value::from(0));
+    /// assert!(close_to("erf(0.5)", 0.5204998778130465));
+    /// assert!(close_to("erf(1)", 0.8427007929497149));
+    /// assert!(close_to("erf(2)", 0.9953222650189527));
+    /// assert!(close_to("erf(1e400)", 1.0));
+    ///
+    /// // Odd symmetry
+    /// for x in ["0.1", "0.5", "1", "2.5"] {
+    ///     let positive = eval(&format!("erf({})", x)).unwrap();
+    ///     let negative = eval(&format!("erf(-{})", x)).unwrap();
+    ///     assert_eq!(negative, (-positive).unwrap());
+    /// }
+    /// ```
+    erf,
     FunctionType::Std,
     |v| {
-        read_vec_values!(v, min, max);
-        Ok(Value::Float(
-            rand::thread_rng().gen_range(min.as_float()?..max.as_float()?),
-        ))
+        let x = v.as_float()?;
+        Ok(Value::Float(x.signum() * (1.0 - erfc_positive(x.abs()))))
     },
-    ValueType::VectorType
+    ValueType::FloatType
 );
 
-// LOGIC
+decl_func!(
+    /// Returns the complementary error function of a real number, that is
+    /// `1 - erf(x)`, without losing precision for large values.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let close_to = |expression: &str, expected: f64| {
+    ///     (eval(expression).unwrap().as_float().unwrap() - expected).abs() < 2e-7
+    /// };
+    /// assert_eq!(eval("erfc(0)").unwrap(), Value::from(1));
+    /// assert!(close_to("erfc(1)", 0.15729920705028513));
+    /// assert!(close_to("erfc(-1)", 1.8427007929497148));
+    /// assert!(close_to("erfc(3)", 2.209049699858544e-5));
+    /// ```
+    erfc,
+    FunctionType::Std,
+    |v| {
+        let x = v.as_float()?;
+        match x < 0.0 {
+            true => Ok(Value::Float(2.0 - erfc_positive(-x))),
+            false => Ok(Value::Float(erfc_positive(x))),
+        }
+    },
+    ValueType::FloatType
+);
 
+/// Returns the complementary error function of a non-negative number, through the
+/// rational approximation 7.1.26 from Abramowitz and Stegun.
+fn erfc_positive(x: f64) -> f64 {
+    if x == 0.0 {
+        return 1.0;
+    }
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let polynomial = [
+        0.254_829_592,
+        -0.284_496_736,
+        1.421_413_741,
+        -1.453_152_027,
+        1.061_405_429,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |sum, coefficient| (sum + coefficient) * t);
+    polynomial * (-x * x).exp()
+}
 
-fn branch(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
-    let condition = arguments[0].eval(context, None, depth)?.as_bool()?;
-    if condition {
-        Ok(arguments[1].eval(context, None, depth)?)
-    } else {
-        Ok(arguments[2].eval(context, None, depth)?)
+/// Returns a random float between the minimum, included, and the maximum, excluded.
+/// Like the other random functions, it uses the context seed, if any.
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn rand(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let values = unbox_parameters(arguments, context, depth)?;
+    read_vec_values!(values, min, max);
+    let (min, max) = (min.as_float()?, max.as_float()?);
+    if min.partial_cmp(&max) != Some(Ordering::Less) {
+        return Err(ErrorType::DomainError {
+            value: Value::Float(max),
+            operation_name: "Random",
+        });
     }
+
+    Ok(Value::Float(context.with_rng(|rng| rng.gen_range(min..max))))
 }
 
-// TRIGONOMETRY
+/// Returns a random float between `0`, included, and `1`, excluded.
+///
+/// With the same `rng_seed`, contexts generate the same sequence of numbers:
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let seeded = || {
+///     let mut context = Context::default();
+///     context.rng_seed = Some(42);
+///     context
+/// };
+/// let sequence = |context: &Context| {
+///     (0..5)
+///         .map(|_| eval_with_static_context("(random(), randint(1, 100))", context).unwrap())
+///         .collect::<Vec<Value>>()
+/// };
+///
+/// let (first, second) = (seeded(), seeded());
+/// let values = sequence(&first);
+///
+/// assert_eq!(values, sequence(&second));
+/// // Numbers change within the sequence
+/// assert_ne!(values[0], values[1]);
+///
+/// let value = eval("random()").unwrap().as_float().unwrap();
+/// assert!((0.0..1.0).contains(&value));
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn random(_arguments: &Vec<Box<Expression>>, context: &Context, _depth: u32) -> EvalResult<Value> {
+    Ok(Value::Float(context.with_rng(|rng| rng.gen::<f64>())))
+}
 
-decl_func!(
-    sin,
-    FunctionType::Trig,
-    |v| Ok(v.as_complex()?.sin()),
-    ValueType::ComplexType
-);
+/// Returns a random integer between the minimum and the maximum, both included.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let value = eval("randint(1, 6)").unwrap().as_int().unwrap();
+///
+/// assert!((1..=6).contains(&value));
+/// assert_eq!(eval("randint(3, 3)").unwrap(), Value::from(3));
+/// assert!(eval("randint(6, 1)").is_err());
+/// assert!(eval("randint(1.5, 6)").is_err());
+/// assert!(matches!(eval("randint()"), Err(ErrorType::MissingFunctionParameters { .. })));
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn randint(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let values = unbox_parameters(arguments, context, depth)?;
+    read_vec_values!(values, min, max);
+    let (min_int, max_int) = (min.as_int()?, max.as_int()?);
+    if min_int > max_int {
+        return Err(ErrorType::DomainError {
+            value: max.clone(),
+            operation_name: "Random integer",
+        });
+    }
 
-decl_func!(
-    cos,
-    FunctionType::Trig,
-    |v| Ok(v.as_complex()?.cos()),
-    ValueType::ComplexType
-);
+    Ok(Value::Int(context.with_rng(|rng| rng.gen_range(min_int..=max_int))))
+}
 
-decl_func!(
-    tan,
-    FunctionType::Trig,
-    |v| Ok(v.as_complex()?.tan()),
-    ValueType::ComplexType
-);
+// STATISTICS
 
 decl_func!(
-    asin,
-    FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.asin()),
-    ValueType::ComplexType
+    /// Returns the arithmetic mean of the numbers, which can also be passed as a
+    /// single vector.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("mean(2, 4, 4, 4, 5, 5, 7, 9)").unwrap(), Value::from(5));
+    /// assert_eq!(eval("mean((1, 2))").unwrap(), Value::from(1.5));
+    /// assert_eq!(eval("mean(1, 2, 2)").unwrap(), eval("5/3").unwrap());
+    /// assert!(eval("mean(1, i)").is_err());
+    /// ```
+    mean,
+    FunctionType::Std,
+    |v| {
+        let sample = read_sample(v)?;
+        let count = Value::from(sample.len() as IntValue);
+        let mut sum = Value::Int(0);
+        for value in sample {
+            sum = (sum + value)?;
+        }
+        sum / count
+    },
+    ValueType::VectorType
 );
 
 decl_func!(
-    acos,
-    FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.acos()),
-    ValueType::ComplexType
-);
+    /// Returns the median of the numbers, which can also be passed as a single
+    /// vector. The median of an even amount of numbers is the mean of the two
+    /// middle ones.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("median(2, 4, 4, 4, 5, 5, 7, 9)").unwrap(), Value::from(4.5));
+    /// assert_eq!(eval("median((3, 1, 2))").unwrap(), Value::from(2));
+    /// assert_eq!(eval("median(7)").unwrap(), Value::from(7));
+    /// ```
+    median,
+    FunctionType::Std,
+    |v| {
+        let mut sample = read_sample(v)?;
+        sample.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
 
-decl_func!(
-    atan,
-    FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.atan()),
-    ValueType::ComplexType
+        let middle = sample.len() / 2;
+        if sample.len() % 2 == 1 {
+            Ok(sample[middle].clone())
+        } else {
+            (sample[middle - 1].clone() + sample[middle].clone())? / Value::Int(2)
+        }
+    },
+    ValueType::VectorType
 );
 
 decl_func!(
-    sinh,
-    FunctionType::Trig,
-    |v| Ok(v.as_complex()?.sinh()),
-    ValueType::ComplexType
+    /// Returns the population standard deviation of the numbers, which can also be
+    /// passed as a single vector. If the vector is followed by `true`, the sample
+    /// standard deviation is returned instead.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("stddev(2, 4, 4, 4, 5, 5, 7, 9)").unwrap(), Value::from(2));
+    /// assert_eq!(eval("stddev((2, 4, 4, 4, 5, 5, 7, 9))").unwrap(), Value::from(2));
+    /// assert_eq!(
+    ///     eval("stddev((2, 4, 4, 4, 5, 5, 7, 9), true)").unwrap(),
+    ///     Value::from(2.13808994)
+    /// );
+    /// assert!(eval("stddev((1, 2), 2)").is_err());
+    /// ```
+    stddev,
+    FunctionType::Std,
+    |v: Value| {
+        let (sample, is_sample) = match &v.as_vector()[..] {
+            [Value::Vector(values), is_sample] => (values.clone(), is_sample.as_bool()?),
+            _ => (v.as_vector(), false),
+        };
+        let sample = read_sample(Value::Vector(sample))?;
+        let count = sample.len() as f64 - if is_sample { 1.0 } else { 0.0 };
+        if count < 1.0 {
+            return Err(ErrorType::DomainError {
+                value: Value::Vector(sample),
+                operation_name: "Sample standard deviation",
+            });
+        }
+
+        let values = sample
+            .iter()
+            .map(|value| value.as_float())
+            .collect::<EvalResult<Vec<f64>>>()?;
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let squares = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+        Ok(Value::Float((squares / count).sqrt()))
+    },
+    ValueType::VectorType
 );
 
+/// Returns the numbers of a sample, which must be real and can not be empty.
+fn read_sample(values: Value) -> EvalResult<Vec<Value>> {
+    let sample = values.as_vector();
+    if sample.is_empty() {
+        return Err(ErrorType::EmptyUnion);
+    }
+    for value in &sample {
+        if value.as_f64().is_none() {
+            return Err(ErrorType::TypeError {
+                expected: ValueType::FloatType,
+                given: value.to_type(),
+            });
+        }
+    }
+    Ok(sample)
+}
+
+// NUMBER THEORY
+
 decl_func!(
-    cosh,
-    FunctionType::Trig,
-    |v| Ok(v.as_complex()?.cosh()),
-    ValueType::ComplexType
+    /// Returns the greatest common divisor of integers, which is never negative.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("gcd(12, 18)").unwrap(), Value::from(6));
+    /// assert_eq!(eval("gcd(12, 18, 8)").unwrap(), Value::from(2));
+    /// assert_eq!(eval("gcd(-12, 18)").unwrap(), Value::from(6));
+    /// assert_eq!(eval("gcd(0, 0)").unwrap(), Value::from(0));
+    /// assert!(eval("gcd(1.5, 3)").is_err());
+    /// ```
+    gcd,
+    FunctionType::Std,
+    |v| {
+        let mut result: u128 = 0;
+        for elem in v.as_vector() {
+            result = result.gcd(&(elem.as_int()?.unsigned_abs() as u128));
+        }
+        Ok(unsigned_to_value(result))
+    },
+    ValueType::VectorType
 );
 
 decl_func!(
-    tanh,
-    FunctionType::Trig,
-    |v| Ok(v.as_complex()?.tanh()),
-    ValueType::ComplexType
+    /// Returns the least common multiple of integers, which is never negative.
+    /// Results which do not fit into an `IntValue` are returned as floats.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("lcm(4, 6)").unwrap(), Value::from(12));
+    /// assert_eq!(eval("lcm(4, 6, 10)").unwrap(), Value::from(60));
+    /// assert_eq!(eval("lcm(-4, 6)").unwrap(), Value::from(12));
+    /// assert_eq!(eval("lcm(0, 5)").unwrap(), Value::from(0));
+    /// assert!(eval("lcm(2, 0.5)").is_err());
+    /// ```
+    lcm,
+    FunctionType::Std,
+    |v| {
+        let mut result: u128 = 1;
+        for elem in v.as_vector() {
+            let n = elem.as_int()?.unsigned_abs() as u128;
+            if n == 0 {
+                return Ok(Value::Int(0));
+            }
+            result = (result / result.gcd(&n))
+                .checked_mul(n)
+                .ok_or(ErrorType::DomainError {
+                    value: elem.clone(),
+                    operation_name: "Least common multiple",
+                })?;
+        }
+        Ok(unsigned_to_value(result))
+    },
+    ValueType::VectorType
 );
 
 decl_func!(
-    asinh,
-    FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.asinh()),
-    ValueType::ComplexType
+    /// Returns the factorial of a non-negative integer, like the `!` operator.
+    /// Results from `21!` onwards do not fit into an `IntValue` and are returned
+    /// as floats, which overflow to infinity from `171!` onwards, without computing
+    /// the product. Non-integer values go through the gamma function, as `gamma(x + 1)`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("factorial(0)").unwrap(), Value::from(1));
+    /// assert_eq!(eval("factorial(5)").unwrap(), Value::from(120));
+    /// assert_eq!(eval("factorial(20)").unwrap(), Value::from(2432902008176640000));
+    /// assert_eq!(eval("factorial(21)").unwrap(), Value::from(51090942171709440000.0));
+    /// assert_eq!(eval("factorial(1e15)").unwrap(), Value::Float(f64::INFINITY));
+    /// assert_eq!(eval("factorial(2.5)").unwrap(), eval("2.5!").unwrap());
+    /// assert!(eval("factorial(-1)").is_err());
+    /// ```
+    factorial,
+    FunctionType::Std,
+    |v: Value| v.factorial(),
+    ValueType::FloatType
 );
 
 decl_func!(
-    acosh,
-    FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.acosh()),
-    ValueType::ComplexType
+    /// Returns the double factorial of an integer, the product of the positive
+    /// integers up to it with its same parity. Both `0!!` and `(-1)!!` are `1`.
+    ///
+    /// There is no `!!` operator: `n!!` is rejected as ambiguous, while `(n!)!` is the
+    /// factorial of a factorial.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("doublefactorial(5)").unwrap(), Value::from(15));
+    /// assert_eq!(eval("doublefactorial(6)").unwrap(), Value::from(48));
+    /// assert_eq!(eval("doublefactorial(0)").unwrap(), Value::from(1));
+    /// assert_eq!(eval("doublefactorial(-1)").unwrap(), Value::from(1));
+    /// assert_eq!(eval("doublefactorial(33)").unwrap(), Value::from(6332659870762850625i64));
+    /// assert!(eval("doublefactorial(34)").unwrap().is_float());
+    /// assert!(eval("doublefactorial(-2)").is_err());
+    /// assert!(eval("doublefactorial(2.5)").is_err());
+    ///
+    /// assert!(matches!(eval("5!!"), Err(ErrorType::AmbiguousFactorial { .. })));
+    /// assert_eq!(eval("(3!)!").unwrap(), Value::from(720));
+    /// ```
+    doublefactorial,
+    FunctionType::Std,
+    |v: Value| v.double_factorial(),
+    ValueType::IntType
 );
 
 decl_func!(
-    atanh,
-    FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.atanh()),
-    ValueType::ComplexType
-);
+    /// Returns the number of combinations of `r` elements out of `n`. Results which
+    /// do not fit into an `IntValue` are returned as floats, or as infinity once they
+    /// overflow.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("nCr(5, 2)").unwrap(), Value::from(10));
+    /// assert_eq!(eval("nCr(5, 0)").unwrap(), Value::from(1));
+    /// assert_eq!(eval("nCr(66, 33)").unwrap(), Value::from(7219428434016265740));
+    /// assert_eq!(eval("nCr(1e11, 5e10)").unwrap(), Value::Float(f64::INFINITY));
+    /// assert!(eval("nCr(2, 5)").is_err());
+    /// assert!(eval("nCr(-5, 2)").is_err());
+    /// ```
+    #[allow(non_snake_case)]
+    nCr,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, n, r);
+        let (n, r) = read_selection(n, r, "Combinations")?;
 
-// COMPLEX
+        // Multiply and divide incrementally, as every partial result is itself
+        // a binomial coefficient.
+        let r = r.min(n - r);
+        let mut result: u128 = 1;
+        for i in 1..=r {
+            match result.checked_mul((n - r + i) as u128) {
+                Some(value) => result = value / i as u128,
+                None => {
+                    return Ok(Value::Float(growing_product(
+                        (1..=r).map(|i| (n - r + i) as f64 / i as f64),
+                    )))
+                }
+            }
+        }
+        Ok(unsigned_to_value(result))
+    },
+    ValueType::VectorType
+);
 
 decl_func!(
-    re,
+    /// Returns the number of permutations of `r` elements out of `n`. Results which
+    /// do not fit into an `IntValue` are returned as floats, or as infinity once they
+    /// overflow.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("nPr(5, 2)").unwrap(), Value::from(20));
+    /// assert_eq!(eval("nPr(5, 5)").unwrap(), Value::from(120));
+    /// assert_eq!(eval("nPr(1e10, 1e10)").unwrap(), Value::Float(f64::INFINITY));
+    /// assert!(eval("nPr(2, 5)").is_err());
+    /// assert!(eval("nPr(5, -1)").is_err());
+    /// ```
+    #[allow(non_snake_case)]
+    nPr,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.re),
-    ValueType::ComplexType
+    |v| {
+        read_vec_values!(v, n, r);
+        let (n, r) = read_selection(n, r, "Permutations")?;
+
+        let mut result: u128 = 1;
+        for i in (n - r + 1)..=n {
+            match result.checked_mul(i as u128) {
+                Some(value) => result = value,
+                None => return Ok(Value::Float(growing_product(((n - r + 1)..=n).map(|i| i as f64)))),
+            }
+        }
+        Ok(unsigned_to_value(result))
+    },
+    ValueType::VectorType
 );
 
 decl_func!(
-    im,
+    /// Returns whether an integer is prime. Negative numbers, `0` and `1` are not.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("isprime(1)").unwrap(), Value::from(false));
+    /// assert_eq!(eval("isprime(2)").unwrap(), Value::from(true));
+    /// assert_eq!(eval("isprime(91)").unwrap(), Value::from(false));
+    /// assert_eq!(eval("isprime(-7)").unwrap(), Value::from(false));
+    /// assert_eq!(eval("isprime(9223372036854775783)").unwrap(), Value::from(true));
+    /// assert_eq!(eval("isprime(3215031751)").unwrap(), Value::from(false));
+    /// assert!(eval("isprime(2.5)").is_err());
+    /// ```
+    isprime,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.im),
-    ValueType::ComplexType
+    |v: Value| {
+        let n = v.as_int()?;
+        Ok(Value::Bool(n > 0 && is_prime(n as u64)))
+    },
+    ValueType::IntType
 );
 
 decl_func!(
-    polar,
+    /// Returns the smallest prime greater than an integer.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("nextprime(-5)").unwrap(), Value::from(2));
+    /// assert_eq!(eval("nextprime(2)").unwrap(), Value::from(3));
+    /// assert_eq!(eval("nextprime(13)").unwrap(), Value::from(17));
+    /// assert_eq!(eval("nextprime(1000000000000)").unwrap(), Value::from(1000000000039i64));
+    /// // The next prime does not fit into an `IntValue`
+    /// assert!(eval("nextprime(9223372036854775783)").is_err());
+    /// assert!(eval("nextprime(2.5)").is_err());
+    /// ```
+    nextprime,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.to_polar().to_vec()),
-    ValueType::ComplexType
+    |v: Value| {
+        let mut candidate = v.as_int()?.max(1);
+        loop {
+            candidate = candidate.checked_add(1).ok_or(ErrorType::DomainError {
+                value: v.clone(),
+                operation_name: "Next prime",
+            })?;
+            if is_prime(candidate as u64) {
+                return Ok(Value::Int(candidate));
+            }
+        }
+    },
+    ValueType::IntType
 );
 
 decl_func!(
-    arg,
+    /// Returns the ordered prime factors of an integer greater than `1`, with
+    /// multiplicity. Large factors are found with Pollard's rho algorithm, so any
+    /// `IntValue` is factorized quickly.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("factorize(12)").unwrap(), Value::from(vec![2, 2, 3]));
+    /// assert_eq!(eval("factorize(13)").unwrap(), Value::from(vec![13]));
+    /// assert_eq!(eval("factorize(3^5)").unwrap(), Value::from(vec![3, 3, 3, 3, 3]));
+    /// assert_eq!(
+    ///     eval("factorize(9223372036854775807)").unwrap(),
+    ///     Value::from(vec![7, 7, 73, 127, 337, 92737, 649657])
+    /// );
+    /// assert_eq!(
+    ///     eval("factorize(999999000001 * 7)").unwrap(),
+    ///     Value::from(vec![7i64, 999999000001])
+    /// );
+    /// // A large prime is its only factor, kept exact
+    /// assert!(matches!(
+    ///     eval("factorize(4611686018427387847)").unwrap(),
+    ///     Value::Int(4611686018427387847)
+    /// ));
+    /// assert!(matches!(
+    ///     eval("factorize(9223372036854775783)").unwrap(),
+    ///     Value::Int(9223372036854775783)
+    /// ));
+    /// assert!(eval("factorize(1)").is_err());
+    /// assert!(eval("factorize(12.5)").is_err());
+    /// ```
+    factorize,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.arg()),
-    ValueType::ComplexType
+    |v: Value| {
+        let n = v.as_int()?;
+        if n < 2 {
+            return Err(ErrorType::DomainError {
+                value: v,
+                operation_name: "Factorization",
+            });
+        }
+
+        let mut factors = prime_factors(n as u64);
+        factors.sort_unstable();
+        Ok(Value::from(
+            factors.into_iter().map(|factor| factor as IntValue).collect::<Vec<IntValue>>(),
+        ))
+    },
+    ValueType::VectorType
 );
 
 decl_func!(
-    norm,
+    /// Returns the digits of a non-negative integer in a base between 2 and 36, from
+    /// the most significant one. Use `frombase` to convert them back.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("tobase(255, 16)").unwrap(), Value::from(vec![15, 15]));
+    /// assert_eq!(eval("tobase(10, 2)").unwrap(), Value::from(vec![1, 0, 1, 0]));
+    /// assert_eq!(eval("tobase(0, 8)").unwrap(), Value::from(vec![0]));
+    /// assert!(eval("tobase(10, 1)").is_err());
+    /// assert!(eval("tobase(10, 37)").is_err());
+    /// assert!(eval("tobase(-10, 2)").is_err());
+    /// ```
+    tobase,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.norm()),
-    ValueType::ComplexType
-);
+    |v| {
+        read_vec_values!(v, n, base);
+        let radix = read_base(base)? as u64;
+        let mut remaining = match u64::try_from(n.as_int()?) {
+            Ok(value) => value,
+            Err(_) => {
+                return Err(ErrorType::DomainError {
+                    value: n.clone(),
+                    operation_name: "To base",
+                })
+            }
+        };
+
+        let mut digits = vec![(remaining % radix) as IntValue];
+        remaining /= radix;
+        while remaining > 0 {
+            digits.push((remaining % radix) as IntValue);
+            remaining /= radix;
+        }
+        digits.reverse();
+        Ok(Value::from(digits))
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Returns the integer with the provided digits in a base between 2 and 36, from
+    /// the most significant one. As there are no string values, digits are numbers,
+    /// so `ff` in base 16 is `(15, 15)`. Literals in bases 2, 8 and 16 can also be
+    /// written with the `0b`, `0o` and `0x` prefixes.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("frombase((15, 15), 16)").unwrap(), Value::from(255));
+    /// assert_eq!(eval("frombase((15, 15), 16)").unwrap(), eval("0xff").unwrap());
+    /// assert_eq!(eval("frombase(tobase(1234, 36), 36)").unwrap(), Value::from(1234));
+    /// assert_eq!(eval("frombase(7, 8)").unwrap(), Value::from(7));
+    /// // Digits must be valid for the base
+    /// assert!(eval("frombase((1, 2), 2)").is_err());
+    /// assert!(eval("frombase((1, -1), 10)").is_err());
+    /// assert!(eval("frombase((1, 0), 40)").is_err());
+    /// ```
+    frombase,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, digits, base);
+        let radix = read_base(base)? as IntValue;
+
+        let mut result: IntValue = 0;
+        for digit in digits.as_vector() {
+            let value = digit.as_int()?;
+            if !(0..radix).contains(&value) {
+                return Err(ErrorType::DomainError {
+                    value: digit,
+                    operation_name: "From base",
+                });
+            }
+            result = result
+                .checked_mul(radix)
+                .and_then(|result| result.checked_add(value))
+                .ok_or(ErrorType::DomainError {
+                    value: digits.clone(),
+                    operation_name: "From base",
+                })?;
+        }
+        Ok(Value::Int(result))
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Returns the bitwise exclusive or of two integers. It is a function because `^`
+    /// is the exponentiation.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("xor(6, 3)").unwrap(), Value::from(5));
+    /// assert_eq!(eval("xor(12, 12)").unwrap(), Value::from(0));
+    /// assert_eq!(eval("xor(-1, 5)").unwrap(), Value::from(-6));
+    /// assert_eq!(eval("xor(6, 3) | 8").unwrap(), Value::from(13));
+    /// assert!(eval("xor(1.5, 2)").is_err());
+    /// ```
+    xor,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, a, b);
+        a.clone().bitwise_xor(b.clone())
+    },
+    ValueType::VectorType
+);
+
+/// Reads a base, which must be an integer between 2 and 36.
+fn read_base(base: &Value) -> EvalResult<u32> {
+    match base.as_int()? {
+        radix @ 2..=36 => Ok(radix as u32),
+        _ => Err(ErrorType::DomainError {
+            value: base.clone(),
+            operation_name: "Base",
+        }),
+    }
+}
+
+/// Reads the `n` and `r` arguments of a selection, which must be integers such
+/// that `0 <= r <= n`.
+fn read_selection(
+    n: &Value,
+    r: &Value,
+    operation_name: &'static str,
+) -> EvalResult<(IntValue, IntValue)> {
+    let (n_int, r_int) = (n.as_int()?, r.as_int()?);
+    if n_int < 0 {
+        return Err(ErrorType::DomainError {
+            value: n.clone(),
+            operation_name,
+        });
+    }
+    if r_int < 0 || r_int > n_int {
+        return Err(ErrorType::DomainError {
+            value: r.clone(),
+            operation_name,
+        });
+    }
+    Ok((n_int, r_int))
+}
+
+/// Returns the product of factors which are not less than `1`, stopping as soon as
+/// it overflows to infinity instead of going through every factor.
+fn growing_product(factors: impl Iterator<Item = f64>) -> f64 {
+    let mut product = 1.0;
+    for factor in factors {
+        product *= factor;
+        if product.is_infinite() {
+            break;
+        }
+    }
+    product
+}
+
+/// Returns an int if the number fits into an `IntValue`, a float otherwise.
+fn unsigned_to_value(n: u128) -> Value {
+    match IntValue::try_from(n) {
+        Ok(value) => Value::Int(value),
+        Err(_) => Value::Float(n as f64),
+    }
+}
+
+/// Returns whether a number is prime using a Miller-Rabin test, which is
+/// deterministic for 64-bit integers with the first twelve primes as bases.
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    for base in BASES {
+        if n.is_multiple_of(base) {
+            return n == base;
+        }
+    }
+
+    let mul_mod = |a: u64, b: u64| (a as u128 * b as u128 % n as u128) as u64;
+    let pow_mod = |mut base: u64, mut exponent: u64| {
+        let mut result = 1;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = mul_mod(result, base);
+            }
+            base = mul_mod(base, base);
+            exponent >>= 1;
+        }
+        result
+    };
+
+    // Write n - 1 as d * 2^s with an odd d
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    BASES.iter().all(|&base| {
+        let mut x = pow_mod(base, d);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Returns the prime factors of a number greater than `1`, in no particular order.
+fn prime_factors(n: u64) -> Vec<u64> {
+    if n == 1 {
+        return vec![];
+    }
+    if is_prime(n) {
+        return vec![n];
+    }
+    if n.is_multiple_of(2) {
+        return [vec![2], prime_factors(n / 2)].concat();
+    }
+
+    // Pollard's rho, with increasing constants until a proper divisor is found
+    let mul_mod = |a: u64, b: u64| (a as u128 * b as u128 % n as u128) as u64;
+    for c in 1.. {
+        let next = |x: u64| ((mul_mod(x, x) as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut divisor) = (2, 2, 1);
+
+        while divisor == 1 {
+            x = next(x);
+            y = next(next(y));
+            divisor = x.abs_diff(y).gcd(&n);
+        }
+
+        if divisor != n {
+            return [prime_factors(divisor), prime_factors(n / divisor)].concat();
+        }
+    }
+    unreachable!()
+}
+
+// LOGIC
+
+/// Evaluates the condition and then only the taken branch.
+fn branch(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let condition = arguments[0].eval_nested(context, None, depth)?.as_bool()?;
+    if condition {
+        arguments[1].eval_nested(context, None, depth)
+    } else {
+        arguments[2].eval_nested(context, None, depth)
+    }
+}
+
+// SERIES
+
+/// Returns the sum of the expression evaluated with the variable bound to every
+/// integer between the start and the end, both included.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// assert_eq!(eval("sum(k, 1, 10, k)").unwrap(), Value::from(55));
+/// assert_eq!(eval("sum(i, 1, 3, i^2)").unwrap(), Value::from(14));
+/// assert_eq!(eval("sum(k, 0, 3, sum(j, 0, k, j))").unwrap(), Value::from(10));
+/// assert_eq!(eval("sum(k, 2, 2, 1/k)").unwrap(), Value::from(0.5));
+/// assert!(eval("sum(k, 3, 1, k)").is_err());
+/// assert!(eval("sum(2, 1, 3, k)").is_err());
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn sum(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    accumulate(arguments, context, depth, Value::Int(0), |a, b| a + b, "Sum")
+}
+
+/// Returns the product of the expression evaluated with the variable bound to every
+/// integer between the start and the end, both included.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// assert_eq!(eval("product(i, 1, 5, i)").unwrap(), Value::from(120));
+/// assert_eq!(eval("product(k, 1, 3, 2)").unwrap(), Value::from(8));
+/// assert!(eval("product(k, 1, 2.5, k)").is_err());
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn product(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    accumulate(arguments, context, depth, Value::Int(1), |a, b| a * b, "Product")
+}
+
+/// Reads the variable, the start and the end of a range, and then applies the
+/// operation to the expression evaluated with the variable bound to every integer
+/// in the range. The variable shadows any other one with the same identifier.
+fn accumulate(
+    arguments: &[Box<Expression>],
+    context: &Context,
+    depth: u32,
+    initial: Value,
+    operation: fn(Value, Value) -> EvalResult<Value>,
+    operation_name: &'static str,
+) -> EvalResult<Value> {
+    let identifier = match &*arguments[0] {
+        Expression::Var(identifier) => identifier.clone(),
+        _ => return Err(ErrorType::InvalidDeclaration),
+    };
+    let start = arguments[1].eval_nested(context, None, depth)?;
+    let end = arguments[2].eval_nested(context, None, depth)?;
+    let (start_int, end_int) = (start.as_int()?, end.as_int()?);
+
+    if start_int > end_int {
+        return Err(ErrorType::DomainError {
+            value: end,
+            operation_name,
+        });
+    }
+    if let settings::IterationLimit::Limit(limit) = context.iteration_limit {
+        if end_int.abs_diff(start_int) >= limit as u64 {
+            return Err(ErrorType::IterationLimitReached { limit });
+        }
+    }
+
+    let mut scope = Context::default();
+    let mut result = initial;
+    for n in start_int..=end_int {
+        scope.add_variable(identifier.clone(), Box::new(Expression::Literal(Value::Int(n))));
+        result = operation(result, arguments[3].eval_nested(context, Some(&scope), depth)?)?;
+    }
+
+    Ok(result)
+}
+
+/// Returns the derivative of a single-argument function at the provided point.
+///
+/// It is computed with a central difference, refined through Richardson
+/// extrapolation, using a step proportional to the magnitude of the point. The
+/// result is exact, up to floating point errors, for polynomials of degree up to 4,
+/// while the relative error for other smooth functions is usually below `1e-10`.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// eval_script("f(t) = t^2; g(t) = 2t^4 - t^3 + 5; h(a, b) = a + b", &mut context).unwrap();
+///
+/// assert_eq!(eval_with_static_context("derivative(f, 3)", &context).unwrap(), Value::from(6));
+/// assert_eq!(eval_with_static_context("derivative(g, 2)", &context).unwrap(), Value::from(52));
+/// assert_eq!(eval_with_static_context("derivative(f, 0)", &context).unwrap(), Value::from(0));
+/// assert_eq!(eval_with_static_context("derivative(g, -1)", &context).unwrap(), Value::from(-11));
+/// // The error grows with the magnitude of the result
+/// assert_eq!(
+///     eval_with_static_context("round(derivative(g, 1000))", &context).unwrap(),
+///     Value::from(7997000000i64)
+/// );
+/// assert_eq!(eval_with_static_context("derivative(sin, 0)", &context).unwrap(), Value::from(1));
+/// assert_eq!(eval_with_static_context("derivative(exp, 1)", &context).unwrap(), eval("e").unwrap());
+/// // Only single-argument functions can be differentiated
+/// assert!(eval_with_static_context("derivative(h, 1)", &context).is_err());
+/// assert!(eval_with_static_context("derivative(k, 1)", &context).is_err());
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn derivative(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let func_name = read_function_reference(&arguments[0], context)?;
+    let x = arguments[1].eval_nested(context, None, depth)?.as_float()?;
+
+    let f = |x| eval_real_function(&func_name, x, context, depth);
+    let central_difference = |h: f64| Ok((f(x + h)? - f(x - h)?) / (2.0 * h));
+
+    let h = f64::EPSILON.powf(0.2) * x.abs().max(1.0);
+    let result = (4.0 * central_difference(h / 2.0)? - central_difference(h)?) / 3.0;
+
+    Ok(Value::Float(result))
+}
+
+/// Returns a root of a single-argument function between the two points, where it
+/// must change sign. The root is found through bisection, until the two points are
+/// adjacent floating point numbers, so the amount of iterations is bounded by the
+/// context iteration limit.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// eval_script("f(x) = x - 2; g(x) = x^2 - 2", &mut context).unwrap();
+///
+/// assert_eq!(eval_with_static_context("solve(f, 0, 5)", &context).unwrap(), Value::from(2));
+/// assert_eq!(eval_with_static_context("solve(g, 3, 0)", &context).unwrap(), eval("sqrt(2)").unwrap());
+/// assert_eq!(eval_with_static_context("solve(cos, 0, 3)", &context).unwrap(), eval("pi/2").unwrap());
+/// // There must be a sign change between the points
+/// assert!(eval_with_static_context("solve(g, -1, 1)", &context).is_err());
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn solve(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let func_name = read_function_reference(&arguments[0], context)?;
+    let mut a = arguments[1].eval_nested(context, None, depth)?.as_float()?;
+    let mut b = arguments[2].eval_nested(context, None, depth)?.as_float()?;
+
+    let f = |x| eval_real_function(&func_name, x, context, depth);
+    let mut f_a = f(a)?;
+    let f_b = f(b)?;
+
+    if f_a == 0.0 {
+        return Ok(Value::Float(a));
+    } else if f_b == 0.0 {
+        return Ok(Value::Float(b));
+    } else if f_a.signum() == f_b.signum() || f_a.is_nan() || f_b.is_nan() {
+        return Err(ErrorType::DomainError {
+            value: Value::Float(b),
+            operation_name: "Solve",
+        });
+    }
+
+    let mut iterations = 0;
+    loop {
+        let middle = a + (b - a) / 2.0;
+        if middle == a || middle == b {
+            return Ok(Value::Float(middle));
+        }
+
+        if let settings::IterationLimit::Limit(limit) = context.iteration_limit {
+            if iterations >= limit {
+                return Err(ErrorType::IterationLimitReached { limit });
+            }
+        }
+        iterations += 1;
+
+        let f_middle = f(middle)?;
+        if f_middle == 0.0 {
+            return Ok(Value::Float(middle));
+        } else if f_middle.signum() == f_a.signum() {
+            a = middle;
+            f_a = f_middle;
+        } else {
+            b = middle;
+        }
+    }
+}
+
+/// Returns the definite integral of a single-argument function between the two
+/// points, or its opposite if the first point is greater than the second.
+///
+/// It is computed through adaptive Simpson's rule, splitting intervals until the
+/// estimated error is below `1e-10` relative to the result. Every split counts as an
+/// iteration against the context limit.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// eval_script("f(x) = x^2; g(x) = 1/x", &mut context).unwrap();
+///
+/// let integral = |input| eval_with_static_context(input, &context).unwrap().as_f64().unwrap();
+/// let assert_close = |a: f64, b: f64| assert!((a - b).abs() < 1e-8, "{} != {}", a, b);
+///
+/// assert_close(integral("integrate(f, 0, 1)"), 1.0 / 3.0);
+/// assert_close(integral("integrate(f, 1, 0)"), -1.0 / 3.0);
+/// assert_close(integral("integrate(sin, 0, pi)"), 2.0);
+/// assert_close(integral("integrate(g, 1, e)"), 1.0);
+/// assert_close(integral("integrate(exp, -1, 1)"), std::f64::consts::E - 1.0 / std::f64::consts::E);
+/// assert_close(integral("integrate(f, 2, 2)"), 0.0);
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn integrate(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let func_name = read_function_reference(&arguments[0], context)?;
+    let a = arguments[1].eval_nested(context, None, depth)?.as_float()?;
+    let b = arguments[2].eval_nested(context, None, depth)?.as_float()?;
+
+    let f = |x| eval_real_function(&func_name, x, context, depth);
+    let mut simpson = Simpson {
+        f: &f,
+        iterations: 0,
+        iteration_limit: context.iteration_limit,
+    };
+
+    let (start, end) = (a.min(b), a.max(b));
+    let middle = start + (end - start) / 2.0;
+    let points = [(start, f(start)?), (middle, f(middle)?), (end, f(end)?)];
+    let whole = Simpson::estimate(points);
+    let integral = simpson.integrate(points, whole, 1e-10 * whole.abs().max(1.0), 50)?;
+
+    Ok(Value::Float(if a > b { -integral } else { integral }))
+}
+
+/// Computes integrals through adaptive Simpson's rule.
+struct Simpson<'a> {
+    /// The function to integrate.
+    f: &'a dyn Fn(f64) -> EvalResult<f64>,
+    /// The amount of intervals split so far.
+    iterations: u32,
+    iteration_limit: settings::IterationLimit,
+}
+
+impl Simpson<'_> {
+    /// Returns the Simpson's rule estimate given the start, middle and end points of
+    /// an interval, with the function values.
+    fn estimate(points: [(f64, f64); 3]) -> f64 {
+        let [(start, f_start), (_, f_middle), (end, f_end)] = points;
+        (end - start) / 6.0 * (f_start + 4.0 * f_middle + f_end)
+    }
+
+    /// Returns the integral over the interval, whose estimate is `whole`, splitting
+    /// it until the error is below the tolerance or the maximum depth is reached.
+    fn integrate(
+        &mut self,
+        points: [(f64, f64); 3],
+        whole: f64,
+        tolerance: f64,
+        max_depth: u32,
+    ) -> EvalResult<f64> {
+        if let settings::IterationLimit::Limit(limit) = self.iteration_limit {
+            if self.iterations >= limit {
+                return Err(ErrorType::IterationLimitReached { limit });
+            }
+        }
+        self.iterations += 1;
+
+        let [start, middle, end] = points;
+        let left_middle = start.0 + (middle.0 - start.0) / 2.0;
+        let right_middle = middle.0 + (end.0 - middle.0) / 2.0;
+        let left_points = [start, (left_middle, (self.f)(left_middle)?), middle];
+        let right_points = [middle, (right_middle, (self.f)(right_middle)?), end];
+
+        let left = Self::estimate(left_points);
+        let right = Self::estimate(right_points);
+        let error = left + right - whole;
+
+        if max_depth == 0 || error.abs() <= 15.0 * tolerance {
+            Ok(left + right + error / 15.0)
+        } else {
+            Ok(self.integrate(left_points, left, tolerance / 2.0, max_depth - 1)?
+                + self.integrate(right_points, right, tolerance / 2.0, max_depth - 1)?)
+        }
+    }
+}
+
+/// Returns the identifier of the function an argument refers to, such as the `f`
+/// in `derivative(f, 2)`.
+fn read_function_reference(argument: &Expression, context: &Context) -> EvalResult<String> {
+    match argument {
+        Expression::Var(identifier)
+            if get_built_in_function(identifier).is_some() || context.is_function(identifier) =>
+        {
+            Ok(identifier.clone())
+        }
+        Expression::Var(identifier) => Err(ErrorType::UnknownFunction {
+            func_name: identifier.clone(),
+        }),
+        _ => Err(ErrorType::InvalidDeclaration),
+    }
+}
+
+/// Evaluates a single-argument function which must return a real value.
+fn eval_real_function(func_name: &str, x: f64, context: &Context, depth: u32) -> EvalResult<f64> {
+    call_function(func_name, vec![Value::Float(x)], context, depth)?.as_float()
+}
+
+/// Evaluates a function with the provided arguments values.
+fn call_function(
+    func_name: &str,
+    arguments: Vec<Value>,
+    context: &Context,
+    depth: u32,
+) -> EvalResult<Value> {
+    Expression::Func(
+        func_name.to_owned(),
+        arguments
+            .into_iter()
+            .map(|argument| Box::new(Expression::Literal(argument)))
+            .collect(),
+    )
+    .eval_nested(context, None, depth)
+}
+
+// LISTS
+
+/// Returns the list of the results of a function applied to every element of a list.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// eval_script("f(x) = x^2; v = [1, 2, 3]", &mut context).unwrap();
+///
+/// assert_eq!(eval_with_static_context("map(f, v)", &context).unwrap(), Value::from(vec![1, 4, 9]));
+/// assert_eq!(eval("map(sqrt, [4, 9])").unwrap(), Value::from(vec![2, 3]));
+/// assert_eq!(eval_with_static_context("map(f, [])", &context).unwrap(), eval("[]").unwrap());
+/// assert!(eval("map(x, [1, 2])").is_err());
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn map(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let func_name = read_function_reference(&arguments[0], context)?;
+    let list = arguments[1].eval_nested(context, None, depth)?;
+
+    let mut results = vec![];
+    for value in list.as_vector() {
+        results.push(call_function(&func_name, vec![value], context, depth)?);
+    }
+    Ok(Value::Vector(results))
+}
+
+/// Returns the list of the elements of a list for which a function returns true.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// eval_script("f(x) = x % 2 == 0", &mut context).unwrap();
+///
+/// assert_eq!(
+///     eval_with_static_context("filter(f, [1, 2, 3, 4])", &context).unwrap(),
+///     Value::from(vec![2, 4])
+/// );
+/// assert_eq!(eval_with_static_context("filter(f, [1, 3])", &context).unwrap(), eval("[]").unwrap());
+/// assert_eq!(eval("filter(isprime, [4, 5, 6, 7])").unwrap(), Value::from(vec![5, 7]));
+/// // The function must return bools
+/// assert!(eval("filter(sqrt, [4, 9])").is_err());
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn filter(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let func_name = read_function_reference(&arguments[0], context)?;
+    let list = arguments[1].eval_nested(context, None, depth)?;
+
+    let mut results = vec![];
+    for value in list.as_vector() {
+        if call_function(&func_name, vec![value.clone()], context, depth)?.as_bool()? {
+            results.push(value);
+        }
+    }
+    Ok(Value::Vector(results))
+}
+
+/// Combines the elements of a list, from the first one, by calling a two-argument
+/// function with the accumulated value and every element, starting from an
+/// initial value.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let mut context = Context::default();
+/// eval_script("f(a, b) = a + b; g(a, b) = 2a + b", &mut context).unwrap();
+///
+/// assert_eq!(eval_with_static_context("reduce(f, 0, [1, 2, 3])", &context).unwrap(), Value::from(6));
+/// assert_eq!(eval_with_static_context("reduce(g, 0, [1, 0, 1])", &context).unwrap(), Value::from(5));
+/// assert_eq!(eval("reduce(max, 0, [3, 7, 2])").unwrap(), Value::from(7));
+/// // An empty list returns the initial value
+/// assert_eq!(eval_with_static_context("reduce(f, 5, [])", &context).unwrap(), Value::from(5));
+/// ```
+#[allow(clippy::vec_box, clippy::ptr_arg)]
+fn reduce(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+    let func_name = read_function_reference(&arguments[0], context)?;
+    let mut result = arguments[1].eval_nested(context, None, depth)?;
+    let list = arguments[2].eval_nested(context, None, depth)?;
+
+    for value in list.as_vector() {
+        result = call_function(&func_name, vec![result, value], context, depth)?;
+    }
+    Ok(result)
+}
+
+// TRIGONOMETRY
+
+decl_func!(
+    /// Returns the sine of the angle, expressed in the context angle unit.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let with_unit = |angle_unit| Context::new(
+    ///     settings::Rounding::Round(8),
+    ///     angle_unit,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    ///
+    /// let radian = with_unit(settings::AngleUnit::Radian);
+    /// assert_eq!(eval_with_static_context("sin(pi/2)", &radian).unwrap(), Value::from(1));
+    ///
+    /// let degree = with_unit(settings::AngleUnit::Degree);
+    /// assert_eq!(eval_with_static_context("sin(90)", &degree).unwrap(), Value::from(1));
+    /// assert_eq!(eval_with_static_context("sin(30)", &degree).unwrap(), Value::from(0.5));
+    ///
+    /// let turn = with_unit(settings::AngleUnit::Turn);
+    /// assert_eq!(eval_with_static_context("sin(0.25)", &turn).unwrap(), Value::from(1));
+    ///
+    /// let gradian = with_unit(settings::AngleUnit::Gradian);
+    /// assert_eq!(eval_with_static_context("sin(100)", &gradian).unwrap(), Value::from(1));
+    /// ```
+    sin,
+    FunctionType::Trig,
+    |v| Ok(v.as_complex()?.sin()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the cosine of the angle, expressed in the context angle unit.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::Round(8),
+    ///     settings::AngleUnit::Degree,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    ///
+    /// assert_eq!(eval("cos(pi)").unwrap(), Value::from(-1));
+    /// assert_eq!(eval_with_static_context("cos(180)", &context).unwrap(), Value::from(-1));
+    /// ```
+    cos,
+    FunctionType::Trig,
+    |v| Ok(v.as_complex()?.cos()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the tangent of the angle, expressed in the context angle unit.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::Round(8),
+    ///     settings::AngleUnit::Turn,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    ///
+    /// assert_eq!(eval("tan(pi/4)").unwrap(), Value::from(1));
+    /// assert_eq!(eval_with_static_context("tan(0.125)", &context).unwrap(), Value::from(1));
+    /// ```
+    tan,
+    FunctionType::Trig,
+    |v| Ok(v.as_complex()?.tan()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the arcsine of the number, expressed in the context angle unit.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let with_unit = |angle_unit| Context::new(
+    ///     settings::Rounding::Round(8),
+    ///     angle_unit,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    ///
+    /// let radian = with_unit(settings::AngleUnit::Radian);
+    /// assert_eq!(eval_with_static_context("asin(sin(0.5))", &radian).unwrap(), Value::from(0.5));
+    ///
+    /// let degree = with_unit(settings::AngleUnit::Degree);
+    /// assert_eq!(eval_with_static_context("asin(1)", &degree).unwrap(), Value::from(90));
+    /// assert_eq!(eval_with_static_context("asin(sin(30))", &degree).unwrap(), Value::from(30));
+    ///
+    /// let turn = with_unit(settings::AngleUnit::Turn);
+    /// assert_eq!(eval_with_static_context("asin(sin(0.1))", &turn).unwrap(), Value::from(0.1));
+    ///
+    /// let gradian = with_unit(settings::AngleUnit::Gradian);
+    /// assert_eq!(eval_with_static_context("asin(sin(50))", &gradian).unwrap(), Value::from(50));
+    /// ```
+    asin,
+    FunctionType::InverseTrig,
+    |v| Ok(v.as_complex()?.asin()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the arccosine of the number, expressed in the context angle unit.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::Round(8),
+    ///     settings::AngleUnit::Degree,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    ///
+    /// assert_eq!(eval_with_static_context("acos(-1)", &context).unwrap(), Value::from(180));
+    /// assert_eq!(eval_with_static_context("acos(cos(120))", &context).unwrap(), Value::from(120));
+    /// ```
+    acos,
+    FunctionType::InverseTrig,
+    |v| Ok(v.as_complex()?.acos()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the arctangent of the number, expressed in the context angle unit.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::Round(8),
+    ///     settings::AngleUnit::Turn,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    ///
+    /// assert_eq!(eval_with_static_context("atan(1)", &context).unwrap(), Value::from(0.125));
+    /// assert_eq!(eval_with_static_context("atan(tan(-0.2))", &context).unwrap(), Value::from(-0.2));
+    /// ```
+    atan,
+    FunctionType::InverseTrig,
+    |v| Ok(v.as_complex()?.atan()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the angle between the positive x axis and the point `(x, y)`, in the
+    /// range from -180 to 180 degrees, expressed in the context angle unit. The angle
+    /// of the origin is 0.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::Round(8),
+    ///     settings::AngleUnit::Degree,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    /// let atan2 = |input| eval_with_static_context(input, &context).unwrap();
+    ///
+    /// assert_eq!(atan2("atan2(1, 1)"), Value::from(45));
+    /// assert_eq!(atan2("atan2(1, -1)"), Value::from(135));
+    /// assert_eq!(atan2("atan2(-1, -1)"), Value::from(-135));
+    /// assert_eq!(atan2("atan2(-1, 1)"), Value::from(-45));
+    /// assert_eq!(atan2("atan2(0, -1)"), Value::from(180));
+    /// assert_eq!(atan2("atan2(0, 0)"), Value::from(0));
+    ///
+    /// assert_eq!(eval("atan2(1, 1)").unwrap(), eval("pi/4").unwrap());
+    /// ```
+    atan2,
+    FunctionType::InverseTrig,
+    |v| {
+        read_vec_values!(v, y, x);
+        Ok(Value::Float(y.as_float()?.atan2(x.as_float()?)))
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Converts an angle from radians to degrees, whatever the context angle unit.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::Round(8),
+    ///     settings::AngleUnit::Turn,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    ///
+    /// assert_eq!(eval("degrees(pi)").unwrap(), Value::from(180));
+    /// assert_eq!(eval_with_static_context("degrees(pi/2)", &context).unwrap(), Value::from(90));
+    /// assert_eq!(eval("degrees(radians(37.5))").unwrap(), Value::from(37.5));
+    /// ```
+    degrees,
+    FunctionType::Std,
+    |v| settings::AngleUnit::Radian.convert_value(settings::AngleUnit::Degree, v),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Converts an angle from degrees to radians, whatever the context angle unit.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("radians(180)").unwrap(), eval("pi").unwrap());
+    /// assert_eq!(eval("radians(-90)").unwrap(), eval("-pi/2").unwrap());
+    /// assert_eq!(eval("radians(degrees(1.25))").unwrap(), Value::from(1.25));
+    /// ```
+    radians,
+    FunctionType::Std,
+    |v| settings::AngleUnit::Degree.convert_value(settings::AngleUnit::Radian, v),
+    ValueType::ComplexType
+);
+
+// HYPERBOLIC
+// Hyperbolic functions do not take angles, so they ignore the angle unit.
+
+decl_func!(
+    /// Returns the hyperbolic sine of the number.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("sinh(0)").unwrap(), Value::from(0));
+    /// assert_eq!(eval("sinh(1)").unwrap(), Value::from(1.17520119));
+    ///
+    /// let context = Context::new(
+    ///     settings::Rounding::NoRounding,
+    ///     settings::AngleUnit::Degree,
+    ///     settings::DepthLimit::NoLimit
+    /// );
+    /// assert_eq!(
+    ///     eval_with_static_context("sinh(1)", &context).unwrap(),
+    ///     Value::from(1.0_f64.sinh())
+    /// );
+    /// ```
+    sinh,
+    FunctionType::Std,
+    |v| Ok(v.as_complex()?.sinh()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the hyperbolic cosine of the number.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("cosh(0)").unwrap(), Value::from(1));
+    /// assert_eq!(eval("cosh(-1)").unwrap(), Value::from(1.54308063));
+    /// ```
+    cosh,
+    FunctionType::Std,
+    |v| Ok(v.as_complex()?.cosh()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the hyperbolic tangent of the number.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("tanh(0)").unwrap(), Value::from(0));
+    /// assert_eq!(eval("tanh(0.5)").unwrap(), Value::from(0.46211716));
+    /// ```
+    tanh,
+    FunctionType::Std,
+    |v| Ok(v.as_complex()?.tanh()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the hyperbolic arcsine of the number.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("asinh(0)").unwrap(), Value::from(0));
+    /// assert_eq!(eval("sinh(asinh(2.5))").unwrap(), Value::from(2.5));
+    /// assert_eq!(eval("asinh(sinh(-3))").unwrap(), Value::from(-3));
+    /// ```
+    asinh,
+    FunctionType::Std,
+    |v| Ok(v.as_complex()?.asinh()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the hyperbolic arccosine of the number. Real numbers lower than 1
+    /// return a `DomainError`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("acosh(1)").unwrap(), Value::from(0));
+    /// assert_eq!(eval("cosh(acosh(2.5))").unwrap(), Value::from(2.5));
+    /// assert!(matches!(eval("acosh(0.99)"), Err(ErrorType::DomainError { .. })));
+    /// ```
+    acosh,
+    FunctionType::Std,
+    |v: Value| {
+        check_real_domain(&v, |x| x >= 1.0, "Hyperbolic arccosine")?;
+        Ok(v.as_complex()?.acosh())
+    },
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the hyperbolic arctangent of the number. Real numbers whose absolute
+    /// value is not lower than 1 return a `DomainError`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("atanh(0)").unwrap(), Value::from(0));
+    /// assert_eq!(eval("tanh(atanh(-0.5))").unwrap(), Value::from(-0.5));
+    /// assert!(matches!(eval("atanh(1)"), Err(ErrorType::DomainError { .. })));
+    /// assert!(matches!(eval("atanh(-1)"), Err(ErrorType::DomainError { .. })));
+    /// ```
+    atanh,
+    FunctionType::Std,
+    |v: Value| {
+        check_real_domain(&v, |x| x.abs() < 1.0, "Hyperbolic arctangent")?;
+        Ok(v.as_complex()?.atanh())
+    },
+    ValueType::ComplexType
+);
+
+/// Returns a `DomainError` if the value is a real number for which the predicate
+/// is false. Complex numbers are always accepted.
+fn check_real_domain(
+    value: &Value,
+    predicate: fn(f64) -> bool,
+    operation_name: &'static str,
+) -> EvalResult<()> {
+    match value.as_f64() {
+        Some(x) if !predicate(x) => Err(ErrorType::DomainError {
+            value: value.try_as_type(ValueType::IntType),
+            operation_name,
+        }),
+        _ => Ok(()),
+    }
+}
+
+// COMPLEX
+
+decl_func!(
+    re,
+    FunctionType::Std,
+    |v| Ok(v.as_complex()?.re),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    im,
+    FunctionType::Std,
+    |v| Ok(v.as_complex()?.im),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    polar,
+    FunctionType::Std,
+    |v| {
+        let (r, theta) = v.as_complex()?.to_polar();
+        Ok(vec![r, theta])
+    },
+    ValueType::ComplexType
+);
+
+decl_func!(
+    arg,
+    FunctionType::Std,
+    |v| Ok(v.as_complex()?.arg()),
+    ValueType::ComplexType
+);
+
+decl_func!(
+    /// Returns the Euclidean length of a vector, whose elements may be complex. The
+    /// length of a complex number is its absolute value.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("norm(3 + 4i)").unwrap(), Value::from(5));
+    /// assert_eq!(eval("norm([3, 4])").unwrap(), Value::from(5));
+    /// assert_eq!(eval("norm([2, 3, 6])").unwrap(), Value::from(7));
+    /// assert_eq!(eval("norm([3i, 4])").unwrap(), Value::from(5));
+    /// ```
+    norm,
+    FunctionType::Std,
+    |v| {
+        let mut result: f64 = 0.0;
+        for elem in v.as_vector() {
+            result = result.hypot(elem.as_complex()?.norm());
+        }
+        Ok(Value::Float(result))
+    },
+    ValueType::VectorType
+);
+
+// VECTORS
+
+decl_func!(
+    /// Returns the dot product of two vectors with the same length.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("dot([1, 2, 3], [4, 5, 6])").unwrap(), Value::from(32));
+    /// assert_eq!(eval("dot([1/2, 1], [1/2, 2])").unwrap(), Value::from(2.25));
+    /// assert!(matches!(
+    ///     eval("dot([1, 2, 3], [4, 5])"),
+    ///     Err(ErrorType::MismatchedArrayLengths { .. })
+    /// ));
+    /// ```
+    dot,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, a, b);
+        let (a, b) = (a.as_vector(), b.as_vector());
+        if a.len() != b.len() {
+            return Err(ErrorType::MismatchedArrayLengths {
+                first: a.len(),
+                second: b.len(),
+                operation_name: "Dot product",
+            });
+        }
+
+        let mut result = Value::Int(0);
+        for (x, y) in a.into_iter().zip(b) {
+            result = (result + (x * y)?)?;
+        }
+        Ok(result)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Returns the cross product of two vectors with three elements.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("cross([1, 0, 0], [0, 1, 0])").unwrap(), Value::from(vec![0, 0, 1]));
+    /// assert_eq!(eval("cross([1, 2, 3], [4, 5, 6])").unwrap(), Value::from(vec![-3, 6, -3]));
+    /// assert!(matches!(
+    ///     eval("cross([1, 2], [3, 4])"),
+    ///     Err(ErrorType::MismatchedArrayLengths { .. })
+    /// ));
+    /// assert!(eval("cross([1, 2, 3], [3, 4])").is_err());
+    /// ```
+    cross,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, a, b);
+        let (a, b) = (a.as_vector(), b.as_vector());
+        for elements in [&a, &b] {
+            if elements.len() != 3 {
+                return Err(ErrorType::MismatchedArrayLengths {
+                    first: elements.len(),
+                    second: 3,
+                    operation_name: "Cross product",
+                });
+            }
+        }
+
+        let component = |i: usize, j: usize| -> EvalResult<Value> {
+            (a[i].clone() * b[j].clone())? - (a[j].clone() * b[i].clone())?
+        };
+        Ok(Value::Vector(vec![
+            component(1, 2)?,
+            component(2, 0)?,
+            component(0, 1)?,
+        ]))
+    },
+    ValueType::VectorType
+);
+
+// MATRICES
+
+decl_func!(
+    /// Returns the product of two matrices, which are lists of rows. The number of
+    /// columns of the first one must be equal to the number of rows of the second one.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(
+    ///     eval("matmul([[1, 2, 3], [4, 5, 6]], [[7, 8], [9, 10], [11, 12]])").unwrap(),
+    ///     Value::from(vec![vec![58, 64], vec![139, 154]])
+    /// );
+    /// assert_eq!(
+    ///     eval("matmul([[1, 2], [3, 4]], [[0, 1], [1, 0]])").unwrap(),
+    ///     Value::from(vec![vec![2, 1], vec![4, 3]])
+    /// );
+    /// assert!(matches!(
+    ///     eval("matmul([[1, 2], [3, 4]], [[1, 2], [3, 4], [5, 6]])"),
+    ///     Err(ErrorType::MismatchedArrayLengths { .. })
+    /// ));
+    /// assert!(eval("matmul([[1, 2], [3]], [[1], [2]])").is_err());
+    /// // Empty matrices
+    /// assert!(matches!(eval("matmul([], [])"), Err(ErrorType::DomainError { .. })));
+    /// assert!(matches!(eval("matmul([[]], [[]])"), Err(ErrorType::DomainError { .. })));
+    /// ```
+    matmul,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, a, b);
+        let (a, b) = (read_matrix(a)?, read_matrix(b)?);
+        let columns = a[0].len();
+        if columns != b.len() {
+            return Err(ErrorType::MismatchedArrayLengths {
+                first: columns,
+                second: b.len(),
+                operation_name: "Matrix multiplication",
+            });
+        }
+
+        let mut result = vec![];
+        for row in &a {
+            let mut result_row = vec![];
+            for j in 0..b[0].len() {
+                let mut element = Value::Int(0);
+                for (value, b_row) in row.iter().zip(&b) {
+                    element = (element + (value.clone() * b_row[j].clone())?)?;
+                }
+                result_row.push(element);
+            }
+            result.push(Value::Vector(result_row));
+        }
+        Ok(Value::Vector(result))
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Returns the transpose of a matrix, which is a list of rows.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(
+    ///     eval("transpose([[1, 2, 3], [4, 5, 6]])").unwrap(),
+    ///     Value::from(vec![vec![1, 4], vec![2, 5], vec![3, 6]])
+    /// );
+    /// assert!(eval("transpose([[1, 2], [3]])").is_err());
+    /// assert!(matches!(eval("transpose([])"), Err(ErrorType::DomainError { .. })));
+    /// ```
+    transpose,
+    FunctionType::Std,
+    |v: Value| {
+        let matrix = read_matrix(&v)?;
+        Ok(Value::Vector(
+            (0..matrix[0].len())
+                .map(|j| Value::Vector(matrix.iter().map(|row| row[j].clone()).collect()))
+                .collect(),
+        ))
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Returns the determinant of a square matrix, which is a list of rows, through
+    /// its LU decomposition with partial pivoting.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("det([[1, 2], [3, 4]])").unwrap(), Value::from(-2));
+    /// assert_eq!(eval("det([[2, 0, 1], [1, 3, 2], [1, 1, 2]])").unwrap(), Value::from(6));
+    /// assert_eq!(eval("det([[1, 2], [2, 4]])").unwrap(), Value::from(0));
+    /// assert_eq!(eval("det([[0, 1], [1, 0]])").unwrap(), Value::from(-1));
+    /// assert!(matches!(
+    ///     eval("det([[1, 2, 3], [4, 5, 6]])"),
+    ///     Err(ErrorType::MismatchedArrayLengths { .. })
+    /// ));
+    /// // Empty matrices
+    /// assert!(matches!(eval("det([])"), Err(ErrorType::DomainError { .. })));
+    /// assert!(matches!(eval("det([[]])"), Err(ErrorType::DomainError { .. })));
+    /// ```
+    det,
+    FunctionType::Std,
+    |v: Value| {
+        let mut matrix = read_matrix(&v)?;
+        let size = matrix.len();
+        if matrix[0].len() != size {
+            return Err(ErrorType::MismatchedArrayLengths {
+                first: size,
+                second: matrix[0].len(),
+                operation_name: "Determinant",
+            });
+        }
+
+        let mut result = match to_upper_triangular(&mut matrix)? {
+            Some(swaps) if swaps % 2 == 1 => Value::Int(-1),
+            Some(_) => Value::Int(1),
+            None => return Ok(Value::Int(0)),
+        };
+        for (k, row) in matrix.iter().enumerate() {
+            result = (result * row[k].clone())?;
+        }
+        Ok(result)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    /// Solves the linear system `A·x = b` for a square matrix `A`, which is a list of
+    /// rows, and a vector `b`, returning the solution vector `x`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(
+    ///     eval("solve_linear([[2, 1, -1], [-3, -1, 2], [-2, 1, 2]], [8, -11, -3])").unwrap(),
+    ///     Value::from(vec![2, 3, -1])
+    /// );
+    /// assert_eq!(
+    ///     eval("solve_linear([[0, 2], [4, 0]], [1, 1])").unwrap(),
+    ///     eval("[1/4, 1/2]").unwrap()
+    /// );
+    /// assert!(matches!(
+    ///     eval("solve_linear([[1, 2], [2, 4]], [1, 2])"),
+    ///     Err(ErrorType::SingularMatrix)
+    /// ));
+    /// assert!(matches!(
+    ///     eval("solve_linear([[1, 2], [3, 4]], [1, 2, 3])"),
+    ///     Err(ErrorType::MismatchedArrayLengths { .. })
+    /// ));
+    /// assert!(matches!(eval("solve_linear([], [])"), Err(ErrorType::DomainError { .. })));
+    /// ```
+    solve_linear,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, a, b);
+        let (mut matrix, b) = (read_matrix(a)?, b.as_vector());
+        let size = matrix.len();
+        if matrix[0].len() != size || b.len() != size {
+            return Err(ErrorType::MismatchedArrayLengths {
+                first: size,
+                second: if b.len() != size { b.len() } else { matrix[0].len() },
+                operation_name: "Linear system",
+            });
+        }
+
+        // Augment the matrix with the known terms
+        for (row, term) in matrix.iter_mut().zip(b) {
+            row.push(term);
+        }
+        if to_upper_triangular(&mut matrix)?.is_none() {
+            return Err(ErrorType::SingularMatrix);
+        }
+
+        // Back substitution
+        let mut solution = vec![Value::Int(0); size];
+        for k in (0..size).rev() {
+            let mut term = matrix[k][size].clone();
+            for j in (k + 1)..size {
+                term = (term - (matrix[k][j].clone() * solution[j].clone())?)?;
+            }
+            solution[k] = (term / matrix[k][k].clone())?;
+        }
+        Ok(Value::Vector(solution))
+    },
+    ValueType::VectorType
+);
+
+/// Reduces the matrix to an upper triangular one through Gaussian elimination with
+/// partial pivoting, over as many columns as rows. Returns the amount of row swaps,
+/// or `None` if the matrix is singular.
+fn to_upper_triangular(matrix: &mut [Vec<Value>]) -> EvalResult<Option<usize>> {
+    let magnitude = |value: &Value| -> EvalResult<f64> { Ok(value.as_complex()?.norm()) };
+    let mut swaps = 0;
+
+    for k in 0..matrix.len() {
+        // Use the largest pivot to limit rounding errors
+        let mut pivot = k;
+        for i in (k + 1)..matrix.len() {
+            if magnitude(&matrix[i][k])? > magnitude(&matrix[pivot][k])? {
+                pivot = i;
+            }
+        }
+        if magnitude(&matrix[pivot][k])? == 0.0 {
+            return Ok(None);
+        }
+        if pivot != k {
+            matrix.swap(pivot, k);
+            swaps += 1;
+        }
+
+        let pivot_row = matrix[k].clone();
+        for row in matrix.iter_mut().skip(k + 1) {
+            let factor = (row[k].clone() / pivot_row[k].clone())?;
+            for (element, pivot_element) in row.iter_mut().zip(&pivot_row).skip(k) {
+                *element = (element.clone() - (factor.clone() * pivot_element.clone())?)?;
+            }
+        }
+    }
+    Ok(Some(swaps))
+}
+
+/// Reads a matrix as a list of rows, which must all have the same amount of elements.
+/// Empty matrices, without rows or columns, return a `DomainError`.
+fn read_matrix(value: &Value) -> EvalResult<Vec<Vec<Value>>> {
+    let matrix = value
+        .as_vector()
+        .iter()
+        .map(|row| row.as_vector())
+        .collect::<Vec<Vec<Value>>>();
+    if matrix.first().is_none_or(|row| row.is_empty()) {
+        return Err(ErrorType::DomainError {
+            value: value.clone(),
+            operation_name: "Matrix",
+        });
+    }
+
+    for row in &matrix {
+        if row.len() != matrix[0].len() {
+            return Err(ErrorType::MismatchedArrayLengths {
+                first: matrix[0].len(),
+                second: row.len(),
+                operation_name: "Matrix",
+            });
+        }
+    }
+    Ok(matrix)
+}
@@ -25,7 +25,8 @@
 //!
 //! The predicate expects a `EvalResult<Value>` as output.
 //!
-//! Remember that functions name can only be alphabetic strings.
+//! Remember that functions name can only be alphabetic strings, optionally
+//! followed by digits like `atan2`.
 //!
 //! Then use the `create_func!` macro to create a `Function` object. It takes
 //! the function name and a `Arguments` as parameters. Then pass the created
@@ -143,6 +144,7 @@ pub mod builtin;
 use crate::{
     objects::Expression,
     out::{ErrorType, EvalResult},
+    prelude::*,
     settings::{self, AngleUnit},
     value::{valuetype::ValueType, Value},
     Context,
@@ -205,6 +207,11 @@ impl Function {
         depth: u32,
     ) -> EvalResult<Value> {
         match self.args {
+            _ if arguments.is_empty() && self.args != Arguments::Const(0) => {
+                return Err(ErrorType::MissingFunctionParameters {
+                    func_name: self.func_identifier.to_owned(),
+                });
+            }
             Arguments::Const(count) => {
                 if arguments.len() != count {
                     return Err(ErrorType::WrongFunctionArgumentsAmount {
@@ -217,13 +224,14 @@ impl Function {
             _ => (),
         }
 
-        let mut joined_context = context.clone();
-        joined_context.join_with(context);
-        if let Some(c) = scope {
-            joined_context.join_with(c);
+        match scope {
+            Some(c) => {
+                let mut joined_context = context.clone();
+                joined_context.join_with(c);
+                (self.func)(arguments, &joined_context, depth)
+            }
+            None => (self.func)(arguments, context, depth),
         }
-
-        (self.func)(arguments, &joined_context, depth)
     }
 }
 
@@ -278,7 +286,7 @@ pub fn unbox_parameters(
     context: &Context,
     depth: u32,
 ) -> EvalResult<Value> {
-    Expression::Union(arguments.clone()).eval(context, None, depth)
+    Expression::Union(arguments.clone()).eval_nested(context, None, depth)
 }
 
 /// Given a function name, a `FunctionType`, a predicate and a target `ValueType` declares a function. It generates
@@ -286,6 +294,8 @@ pub fn unbox_parameters(
 ///
 /// The predicate expects a `Value` as parameter and an `EvalResult<Value>` as output.
 ///
+/// Doc comments and other attributes can be placed before the function name.
+///
 /// ## Examples
 /// ```
 /// // Remember to import everything under the `function` module.
@@ -345,7 +355,8 @@ pub fn unbox_parameters(
 ///
 #[macro_export]
 macro_rules! decl_func {
-    ( $identifier:ident, $func_type:expr, $predicate:expr, $target:expr ) => {
+    ( $(#[$attribute:meta])* $identifier:ident, $func_type:expr, $predicate:expr, $target:expr ) => {
+        $(#[$attribute])*
         fn $identifier(
             arguments: &Vec<Box<Expression>>,
             context: &Context,
@@ -2,6 +2,7 @@ use crate::{
     objects::Expression,
     objects::Request,
     out::{ErrorType, EvalResult},
+    prelude::*,
     token::tokentype::TokenType,
     tree::{Node, Tree},
 };
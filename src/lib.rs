@@ -13,6 +13,7 @@
 //! * Supports **multiple value types**:
 //!     * Bool,
 //!     * Int,
+//!     * [Rational](num::rational::Rational64),
 //!     * Float,
 //!     * [Complex](num::complex::Complex64),
 //!     * Vector
@@ -39,6 +40,18 @@
 //! assert_eq!(eval("re(10+3i)").unwrap(), Value::from(10));
 //! ```
 //!
+//! Divisions of integers are exact, so `1/2` is the rational `1/2` rather than `0.5`.
+//! Rationals are not affected by the context rounding: use `as_float` to get a decimal.
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! let two_thirds = eval("2/3").unwrap();
+//! assert_eq!(two_thirds.to_string(), "2/3");
+//! assert_eq!(two_thirds.round(settings::Rounding::Round(2)), two_thirds);
+//! assert_eq!(two_thirds.as_float().unwrap(), 2.0 / 3.0);
+//! ```
+//!
 //! Using **contexts**:
 //!
 //! ```
@@ -84,6 +97,89 @@
 //! was possible since the value had no decimal part and it was a one. If these conditions were not
 //! met, the cast would have failed.
 //!
+//! Integer literals can also be written in hexadecimal, octal or binary:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("0x1F").unwrap(), Value::from(31));
+//! assert_eq!(eval("0o17").unwrap(), Value::from(15));
+//! assert_eq!(eval("0b1010").unwrap(), Value::from(10));
+//! ```
+//!
+//! Underscores can be used to separate digits:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("1_000.000_1").unwrap(), Value::from(1000.0001));
+//! assert!(eval("1__000").is_err());
+//! ```
+//!
+//! Numbers can be written in scientific notation. An `e` (or `E`) directly following a
+//! number is an exponent only if it is followed by digits, optionally signed, otherwise
+//! it is the Euler's number:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("1.5e3").unwrap(), Value::from(1500));
+//! assert_eq!(eval("2E-4").unwrap(), Value::from(0.0002));
+//! assert_eq!(eval("3e2").unwrap(), Value::from(300));
+//! // Multiplication by the Euler's number
+//! assert_eq!(eval("3e").unwrap(), eval("3*e").unwrap());
+//! ```
+//!
+//! Angles can be written in degrees, minutes and seconds, as in `30d15m20s`, and are read
+//! as decimal degrees. Any component can be omitted, but a single minutes or seconds
+//! component is a multiplication instead, so `2m` multiplies `2` by `m`. A declared
+//! variable or function named `d`, `m` or `s` takes precedence over the angle. The literal
+//! is a plain number of degrees, which is not converted to the context angle unit: it is
+//! only meaningful with `AngleUnit::Degree`.
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("1d30m").unwrap(), Value::from(1.5));
+//! assert_eq!(eval("30d").unwrap(), Value::from(30));
+//! assert_eq!(eval("15m36s").unwrap(), Value::from(0.26));
+//! assert_eq!(eval("-10d0m36s").unwrap(), Value::from(-10.01));
+//! assert!(matches!(eval("10d60m"), Err(ErrorType::FailedParse { .. })));
+//! assert!(matches!(eval("10d30m60s"), Err(ErrorType::FailedParse { .. })));
+//!
+//! let degree = Context::new(
+//!     settings::Rounding::Round(8),
+//!     settings::AngleUnit::Degree,
+//!     settings::DepthLimit::NoLimit,
+//! );
+//! assert_eq!(eval_with_static_context("sin(30d)", &degree).unwrap(), Value::from(0.5));
+//!
+//! let mut context = Context::default();
+//! eval_with_mutable_context("d = 5", &mut context).unwrap();
+//! assert_eq!(eval_with_mutable_context("2d", &mut context).unwrap(), Some(Value::from(10)));
+//! assert_eq!(eval_with_mutable_context("1.5d", &mut context).unwrap(), Some(Value::from(7.5)));
+//! ```
+//!
+//! Lists are written between square brackets, and are stored as vectors. Operations
+//! between a list and a number apply to every element, while operations between two
+//! lists apply to the elements in the same position, so the lists must have the same
+//! length. Elements are accessed by their index, starting from `0`:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("[1, 2, 3] + 1").unwrap(), Value::from(vec![2, 3, 4]));
+//! assert_eq!(eval("[1, 2] * [3, 4]").unwrap(), Value::from(vec![3, 8]));
+//! assert!(matches!(
+//!     eval("[1, 2] + [1, 2, 3]"),
+//!     Err(ErrorType::MismatchedArrayLengths { .. })
+//! ));
+//! assert_eq!(eval("[4, 5, 6][1]").unwrap(), Value::from(5));
+//! assert_eq!(eval("[[1, 2], [3, 4]][1][0]").unwrap(), Value::from(3));
+//! assert_eq!(eval("len([])").unwrap(), Value::from(0));
+//! assert!(eval("[1, 2][2]").is_err());
+//! ```
+//!
 //! ## Operators
 //! **Binary** operators:
 //!
@@ -92,60 +188,303 @@
 //! | ^  | Exponentiation                                       | 90 |
 //! | /  | Division                                             | 70 |
 //! | *  | Multiplication                                       | 70 |
-//! | %  | Modulo                                               | 70 |
+//! | %  | Modulo. The result has the sign of the dividend      | 70 |
+//! | // | Floor division. Rounds the quotient down             | 70 |
 //! | +  | Sum                                                  | 60 |
 //! | -  | Subtraction                                          | 60 |
 //! | <  | Less than                                            | 50 |
 //! | >  | Greater than                                         | 50 |
 //! | <= | Less or equal to                                     | 50 |
 //! | >= | Greater or equal to                                  | 50 |
+//! | << | Bitwise left shift                                   | 45 |
+//! | >> | Bitwise right shift                                  | 45 |
 //! | == | Equal to                                             | 40 |
 //! | != | Not equal to                                         | 40 |
+//! | &  | Bitwise AND                                          | 36 |
+//! | &#124; | Bitwise OR                                       | 34 |
 //! | && | Logical AND                                          | 30 |
 //! | &#124;&#124; | Logical OR                                 | 20 |
 //! | ,  | Aggregation. Creates vectors                         | 10 |
 //! | =  | Assignment. Used for functions and vars declarations | 0  |
 //!
+//! Bitwise operators only accept integer values, and a left shift which overflows returns
+//! a float, as a multiplication would. Note that `^` is always an exponentiation, so
+//! the exclusive or is the `xor(a, b)` function.
+//! Floor division always returns an integer, rounding toward negative infinity.
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("6 & 3").unwrap(), Value::from(2));
+//! assert_eq!(eval("1 << 4").unwrap(), Value::from(16));
+//! assert_eq!(eval("xor(6, 3)").unwrap(), Value::from(5));
+//! assert_eq!(eval("7 // 2").unwrap(), Value::from(3));
+//! assert_eq!(eval("(-7) // 2").unwrap(), Value::from(-4));
+//! ```
+//!
+//! The Unicode symbols `×`, `÷`, `−`, `≤`, `≥` and `≠` can be used in place of `*`, `/`, `-`,
+//! `<=`, `>=` and `!=`:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("3 × 4 ÷ 2").unwrap(), Value::from(6));
+//! assert_eq!(eval("−2 × 3 - 1").unwrap(), Value::from(-7));
+//! assert_eq!(eval("10 − 2*−3 ÷ (4 - 1)").unwrap(), Value::from(12));
+//! assert_eq!(eval("3 ≤ 3").unwrap(), Value::from(true));
+//! assert_eq!(eval("2 ≥ 3").unwrap(), Value::from(false));
+//! assert_eq!(eval("2 ≠ 3").unwrap(), Value::from(true));
+//! assert_eq!(eval("1 + 1 ≤ 2 ≠ 3 ≥ 4").unwrap(), eval("1 + 1 <= 2 != 3 >= 4").unwrap());
+//! ```
+//!
+//! Comparisons and logical operators return bools, which are displayed as `true` and
+//! `false`. Other operators treat them as `1` and `0`, returning numbers:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("3 < 5").unwrap(), Value::from(true));
+//! assert_eq!(eval("2 == 2.0").unwrap(), Value::from(true));
+//! assert_eq!(eval("(3 < 5) + 1").unwrap(), Value::from(2));
+//! assert_eq!(eval("true * true").unwrap(), Value::Int(1));
+//! ```
+//!
 //! **Unary** operators:
 //!
 //! | Operator | Description | Precedence |
 //! |----------|-------------|------------|
 //! | ! | Logical NOT | 80 |
-//! | - | Negation    | 60 |
+//! | - | Negation    | 80 |
+//!
+//! Unary operators apply to what follows them, so `-2^2` is `-(2^2)` and `-7 // 2` is
+//! `(-7) // 2`, while an operator after another one is part of its operand:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("-2^2").unwrap(), Value::from(-4));
+//! assert_eq!(eval("(-2)^2").unwrap(), Value::from(4));
+//! assert_eq!(eval("-7 // 2").unwrap(), Value::from(-4));
+//! assert_eq!(eval("2^-3").unwrap(), Value::from(0.125));
+//! assert_eq!(eval("2*-3").unwrap(), Value::from(-6));
+//! assert_eq!(eval("3 - -2").unwrap(), Value::from(5));
+//! ```
+//!
+//! The `√` symbol is the square root of what follows it, like a function without brackets:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("√16").unwrap(), Value::from(4));
+//! assert_eq!(eval("2√9").unwrap(), Value::from(6));
+//! assert_eq!(eval("√(7 + 2) - 1").unwrap(), Value::from(2));
+//! assert_eq!(eval("2π").unwrap(), eval("2 * pi").unwrap());
+//! assert_eq!(eval("√π").unwrap(), eval("sqrt(pi)").unwrap());
+//! ```
+//!
+//! Superscript digits following a value are an exponent:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("3²").unwrap(), Value::from(9));
+//! assert_eq!(eval("2³ + 1").unwrap(), Value::from(9));
+//! assert_eq!(eval("2¹⁰").unwrap(), Value::from(1024));
+//! assert_eq!(eval("2π²").unwrap(), eval("2 * pi^2").unwrap());
+//! assert_eq!(eval("-3²").unwrap(), Value::from(-9));
+//! assert!(matches!(eval("²"), Err(ErrorType::MissingOperatorArgument { .. })));
+//! ```
+//!
+//! **Postfix** operators:
+//!
+//! | Operator | Description | Precedence |
+//! |----------|-------------|------------|
+//! | ! | Factorial   | 95 |
+//! | % | Percentage  | 95 |
+//!
+//! Factorials can not follow each other: `5!!` is rejected as ambiguous, so write
+//! `doublefactorial(5)` for the double factorial or `(5!)!` for the factorial of a factorial.
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("5!").unwrap(), Value::from(120));
+//! assert_eq!(eval("3!^2").unwrap(), Value::from(36));
+//! assert_eq!(eval("(3!)!").unwrap(), Value::from(720));
+//! assert!(eval("(-1)!").is_err());
+//! assert!(matches!(eval("5!!"), Err(ErrorType::AmbiguousFactorial { .. })));
+//! ```
+//!
+//!
+//! A `%` is a percentage, dividing the value before it by 100, when it is not followed
+//! by a value. Otherwise it is a modulo, so `7 % 3` is `1`. A `-` right after a `%` is a
+//! negation, so `7 % -3` and `20% - 5` are modulos, unless the value before the `%` is
+//! added or subtracted: `100 - 10% - 5` is `(100 - 10%) - 5`. Write `(20%) - 5` to subtract
+//! from a percentage. Percentages follow these rules:
+//!
+//! * `x%` is `x/100`, so `20%` is `0.2`;
+//! * `x% of y` is `x% * y`, so `20% of 50` is `10`;
+//! * when a percentage is directly the right operand of `+` or `-`, the left operand
+//!   is increased or decreased by that percentage: `x + y%` is `x*(1 + y%)` and
+//!   `x - y%` is `x*(1 - y%)`. Only the operand right before the `+` or `-` is
+//!   affected, following the usual precedence, so `2*50 + 10%` is `(2*50)*(1 + 10%)`
+//!   while `2 + 50*10%` is `2 + 5`.
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("20%").unwrap(), Value::from(0.2));
+//! assert_eq!(eval("20% of 50").unwrap(), Value::from(10));
+//! assert_eq!(eval("100 + 10%").unwrap(), Value::from(110));
+//! assert_eq!(eval("100 - 10%").unwrap(), Value::from(90));
+//! assert_eq!(eval("2*50 + 10%").unwrap(), Value::from(110));
+//! assert_eq!(eval("2 + 50*10%").unwrap(), Value::from(7));
+//! assert_eq!(eval("50 + 20% of 10").unwrap(), Value::from(52));
+//! assert_eq!(eval("100 - 10% - 5").unwrap(), Value::from(85));
+//! assert_eq!(eval("(20%) - 5").unwrap(), Value::from(-4.8));
+//! assert_eq!(eval("20% + 5").unwrap(), Value::from(5.2));
+//! assert_eq!(eval("7 % 3").unwrap(), Value::from(1));
+//! assert_eq!(eval("7 % -3").unwrap(), Value::from(1));
+//! assert_eq!(eval("20% - 5").unwrap(), Value::from(0));
+//! ```
+//! Absolute values can be written between bars. A bar opens an absolute value if
+//! it is found where an operand is expected, otherwise it closes the last opened
+//! one. A bar which follows a value when none is open is a bitwise or, so use
+//! `2*|x|` or `sin(|x|)` instead of `2|x|` or `sin|x|`.
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("|-5|").unwrap(), Value::from(5));
+//! assert_eq!(eval("2*|3-9|").unwrap(), Value::from(12));
+//! assert_eq!(eval("|-1| + |-2|").unwrap(), Value::from(3));
+//! assert_eq!(eval("||-2| - 5|").unwrap(), Value::from(3));
+//! ```
 //!
 //! ## Functions
 //!
 //! | Function | Parameters Amount          | Description                                                   |
 //! |----------|----------------------------|---------------------------------------------------------------|
+//! | `len`    | >=1                        | Returns the number of elements of a list.                     |
 //! | `min`    | >=1                        | Returns the minimum value.                                    |
 //! | `max`    | >=1                        | Returns the maximum value.                                    |
+//! | `clamp`  | 3 (x, lower, upper)        | Restricts the number between the lower and upper bound.       |
+//! | `mean`   | >=1                        | Returns the arithmetic mean of the values.                    |
+//! | `median` | >=1                        | Returns the median of the values.                             |
+//! | `stddev` | >=1 (values, sample)       | Returns the population, or sample, standard deviation.        |
 //! | `floor`  | 1                          | Returns the greatest lower integer.                           |
 //! | `ceil`   | 1                          | Returns the lowest greater integer.                           |
 //! | `round`  | 1                          | Returns the rounded integer.                                  |
+//! | `trunc`  | 1                          | Returns the integer part of the number.                       |
+//! | `sign`   | 1                          | Returns -1, 0 or 1 depending on the sign of the number.       |
 //! | `ln`     | 1                          | Returns the natural log of the number.                        |
-//! | `log`    | 2 (base, arg)              | Returns the logarithm of the number with the specified base.  |
+//! | `log`    | 1 or 2 (arg, base)         | Returns the logarithm of the number with the specified base, or base 10. |
 //! | `exp`    | 1                          | Returns e^(arg).                                              |
+//! | `erf`    | 1                          | Returns the error function of a number.                       |
+//! | `erfc`   | 1                          | Returns the complementary error function of a number.         |
 //! | `abs`    | 1                          | Returns the absolute value of a number.                       |
 //! | `sqrt`   | 1                          | Returns the square root of a number.                          |
+//! | `cbrt`   | 1                          | Returns the cube root of a number.                            |
+//! | `root`   | 2 (arg, n)                 | Returns the nth root of a number.                             |
+//! | `hypot`  | >=1                        | Returns the square root of the sum of the squares.            |
 //! | `rand`   | 2 (min, max)               | Returns a random float between the two number specified.      |
+//! | `random` | 0                          | Returns a random float between 0, included, and 1, excluded.  |
+//! | `randint` | 2 (min, max)              | Returns a random integer between the two numbers, included.   |
+//! | `gcd`    | >=1                        | Returns the greatest common divisor of the integers.          |
+//! | `lcm`    | >=1                        | Returns the least common multiple of the integers.            |
+//! | `factorial` | 1                       | Returns the factorial of a non-negative integer.              |
+//! | `doublefactorial` | 1                 | Returns the double factorial of an integer not less than -1.  |
+//! | `gamma`  | 1                          | Returns the gamma function of a number.                       |
+//! | `nCr`    | 2 (n, r)                   | Returns the number of combinations of r elements out of n.    |
+//! | `nPr`    | 2 (n, r)                   | Returns the number of permutations of r elements out of n.    |
+//! | `isprime` | 1                        | Returns whether an integer is prime.                          |
+//! | `nextprime` | 1                      | Returns the smallest prime greater than an integer.           |
+//! | `factorize` | 1                      | Returns the ordered prime factors of an integer.              |
+//! | `tobase` | 2 (n, base)                 | Returns the digits of an integer in a base between 2 and 36.  |
+//! | `frombase` | 2 (digits, base)        | Returns the integer with the provided digits in a base.       |
+//! | `xor`    | 2 (a, b)                   | Returns the bitwise exclusive or of two integers.             |
 //! | `branch` | 3 (condition, true, false) | Returns the second argument if the condition is true, the third if it is false. |
+//! | `if`     | 3 (condition, true, false) | Same as `branch`.                                             |
+//! | `sum`    | 4 (var, start, end, expr)  | Returns the sum of the expression over the integer range.     |
+//! | `product`| 4 (var, start, end, expr)  | Returns the product of the expression over the integer range. |
+//! | `derivative` | 2 (function, x)        | Returns the numerical derivative of a one-argument function at x. |
+//! | `solve`  | 3 (function, a, b)         | Returns a root of a one-argument function between a and b.    |
+//! | `integrate` | 3 (function, a, b)      | Returns the definite integral of a one-argument function from a to b. |
+//! | `map`    | 2 (function, list)         | Returns the results of a function applied to every element.   |
+//! | `filter` | 2 (function, list)         | Returns the elements for which a function returns true.       |
+//! | `reduce` | 3 (function, init, list)   | Combines the elements of a list with a two-argument function. |
 //! | `sin`    | 1                          | Returns the sine of the angle.                                |
 //! | `cos`    | 1                          | Returns the cosine of the angle.                              |
 //! | `tan`    | 1                          | Returns the tangent of the angle.                             |
-//! | `asin`   | 1                          | Returns the arcsine of the angle.                             |
-//! | `acos`   | 1                          | Returns the arccosine of the angle.                           |
-//! | `atan`   | 1                          | Returns the arctangent of the angle.                          |
-//! | `sinh`   | 1                          | Returns the hyperbolic sine of the angle.                     |
-//! | `cosh`   | 1                          | Returns the hyperbolic cosine of the angle.                   |
-//! | `tanh`   | 1                          | Returns the hyperbolic tangent of the angle.                  |
-//! | `asinh`  | 1                          | Returns the hyperbolic arcsine of the angle.                  |
-//! | `acosh`  | 1                          | Returns the hyperbolic arccosine of the angle.                |
-//! | `atanh`  | 1                          | Returns the hyperbolic arctangent of the angle.               |
+//! | `asin`   | 1                          | Returns the arcsine of the number as an angle.                |
+//! | `acos`   | 1                          | Returns the arccosine of the number as an angle.              |
+//! | `atan`   | 1                          | Returns the arctangent of the number as an angle.             |
+//! | `atan2`  | 2 (y, x)                   | Returns the angle of the point (x, y).                        |
+//! | `degrees` | 1                         | Converts an angle from radians to degrees.                    |
+//! | `radians` | 1                         | Converts an angle from degrees to radians.                    |
+//! | `sinh`   | 1                          | Returns the hyperbolic sine of the number.                    |
+//! | `cosh`   | 1                          | Returns the hyperbolic cosine of the number.                  |
+//! | `tanh`   | 1                          | Returns the hyperbolic tangent of the number.                 |
+//! | `asinh`  | 1                          | Returns the hyperbolic arcsine of the number.                 |
+//! | `acosh`  | 1                          | Returns the hyperbolic arccosine of the number.               |
+//! | `atanh`  | 1                          | Returns the hyperbolic arctangent of the number.              |
 //! | `re`     | 1                          | Returns the natural part of the number.                       |
 //! | `im`     | 1                          | Returns the imaginary part of the number.                     |
 //! | `polar`  | 1                          | Returns the polar form (r, theta) of the complex number.      |
 //! | `arg`    | 1                          | Returns the principal arg of the number.                      |
-//! | `norm`   | 1                          | Returns the length of the vector (re, im).                    |
+//! | `norm`   | >=1                        | Returns the length of a vector or of a complex number.        |
+//! | `dot`    | 2 (a, b)                   | Returns the dot product of two vectors.                       |
+//! | `cross`  | 2 (a, b)                   | Returns the cross product of two vectors with three elements. |
+//! | `matmul` | 2 (a, b)                   | Returns the product of two matrices, given as lists of rows.  |
+//! | `transpose` | 1                      | Returns the transpose of a matrix.                            |
+//! | `det`    | 1                          | Returns the determinant of a square matrix.                   |
+//! | `solve_linear` | 2 (A, b)             | Solves the linear system `A·x = b` for a square matrix `A`.   |
+//!
+//! `log` takes the number first and the base second, so `log(8, 2)` is `3`. This is a
+//! breaking change: earlier versions took the base first, as in `log(2, 8)`. Complex
+//! numbers have their principal logarithm.
+//!
+//! `branch` and `if` only evaluate the taken branch, so the other one can not fail:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! let mut context = Context::default();
+//! eval_with_mutable_context("f(x) = if(x != 0, 1/x, 0)", &mut context).unwrap();
+//!
+//! assert_eq!(eval_with_static_context("f(0)", &context).unwrap(), Value::from(0));
+//! assert_eq!(eval_with_static_context("f(4)", &context).unwrap(), Value::from(0.25));
+//! ```
+//!
+//! ## Constants
+//!
+//! | Constant | Value                                  |
+//! |----------|----------------------------------------|
+//! | `pi`, `π` | The ratio of a circumference to its diameter, π. |
+//! | `e`      | The Euler's number.                    |
+//! | `tau`    | 2π.                                    |
+//! | `phi`    | The golden ratio, (1+√5)/2.            |
+//! | `i`      | The imaginary unit.                    |
+//! | `true`   | The true bool.                         |
+//! | `false`  | The false bool.                        |
+//!
+//! Variables with the same identifier as a constant shadow it, while function parameters
+//! shadow both:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! assert_eq!(eval("tau").unwrap(), eval("2pi").unwrap());
+//! assert_eq!(eval("phi").unwrap(), eval("(1+sqrt(5))/2").unwrap());
+//!
+//! let mut context = Context::default();
+//! context.add_variable(String::from("e"), Box::new(Expression::Literal(Value::from(3))));
+//! assert_eq!(eval_with_static_context("2e", &context).unwrap(), Value::from(6));
+//!
+//! eval_with_mutable_context("f(e) = e^2", &mut context).unwrap();
+//! assert_eq!(eval_with_static_context("f(4)", &context).unwrap(), Value::from(16));
+//! assert_eq!(eval("e").unwrap(), eval("exp(1)").unwrap());
+//! ```
 //!
 //! ## Context
 //!
@@ -164,6 +503,141 @@
 //!     settings::AngleUnit::Degree,
 //!     settings::DepthLimit::NoLimit
 //! );
+//!
+//! // Generate a custom context with a builder, setting only some settings
+//! let mut built = Context::builder()
+//!     .angle_unit(settings::AngleUnit::Degree)
+//!     .variable("a", parse("2").unwrap())
+//!     .build();
+//! ```
+//!
+//! Variables may depend on other variables, but not on themselves, either directly or
+//! through other declarations or function calls. Evaluating such a variable returns an
+//! error naming the variables involved, however it was declared:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! let mut context = Context::default();
+//!
+//! eval_script("a = b + 1; b = a + 1; c = c; f(x) = d + x; d = f(1)", &mut context).unwrap();
+//!
+//! assert_eq!(
+//!     eval_with_static_context("a", &context).unwrap_err().to_string(),
+//!     "MATH ERROR: circular reference between variables `a`, `b`."
+//! );
+//! assert_eq!(
+//!     eval_with_static_context("2c", &context).unwrap_err().to_string(),
+//!     "MATH ERROR: circular reference between variables `c`."
+//! );
+//! assert!(matches!(
+//!     eval_with_static_context("d", &context),
+//!     Err(ErrorType::CircularReference { .. })
+//! ));
+//!
+//! let built = Context::builder()
+//!     .variable("g", parse("g + 1").unwrap())
+//!     .build();
+//! assert!(matches!(
+//!     eval_with_static_context("g", &built),
+//!     Err(ErrorType::CircularReference { .. })
+//! ));
+//! ```
+//!
+//! ### Implicit multiplication
+//!
+//! Adjacent factors are multiplied, with the same precedence as `*`, so `2x`, `2(3 + 4)`,
+//! `(a)(b)` and `2pi` are products, while `sin(x)` stays a function call. Set
+//! `implicit_multiplication` to `false` to require explicit operators:
+//!
+//! ```rust
+//! use num_parser2::*;
+//!
+//! let mut context = Context::default();
+//! eval_with_mutable_context("x = 5", &mut context).unwrap();
+//!
+//! assert_eq!(eval_with_static_context("2x", &context).unwrap(), Value::from(10));
+//! assert_eq!(eval_with_static_context("2(3)", &context).unwrap(), Value::from(6));
+//! assert_eq!(eval_with_static_context("1 + 2x^2", &context).unwrap(), Value::from(51));
+//!
+//! context.implicit_multiplication = false;
+//!
+//! assert!(matches!(
+//!     eval_with_static_context("2x", &context),
+//!     Err(ErrorType::MissingOperator { .. })
+//! ));
+//! assert!(eval_with_static_context("2(3)", &context).is_err());
+//! assert_eq!(eval_with_static_context("2*x", &context).unwrap(), Value::from(10));
+//! assert_eq!(eval_with_static_context("sin(0)", &context).unwrap(), Value::from(0));
+//! ```
+//!
+//! ### Step limit
+//!
+//! Since expressions such as a huge `sum` can take a long time to evaluate even without
+//! recursion, the amount of expressions evaluated by a single evaluation can be bounded
+//! with `step_limit`, which is useful when evaluating untrusted input:
+//!
+//! ```rust
+//! use num_parser2::*;
+//!
+//! let mut context = Context::default();
+//! context.step_limit = Some(10_000);
+//!
+//! assert!(matches!(
+//!     eval_with_static_context("sum(k, 1, 100000, k^2)", &context),
+//!     Err(ErrorType::StepLimitReached { limit: 10_000 })
+//! ));
+//! assert_eq!(eval_with_static_context("sum(k, 1, 100, k)", &context).unwrap(), Value::from(5050));
+//! ```
+//!
+//! ### Memoization
+//!
+//! Recursive functions can be slow, as they may be called many times with the same
+//! arguments. With `memoize` enabled, the results of user-defined functions are cached
+//! during each evaluation:
+//!
+//! ```
+//! use num_parser2::*;
+//!
+//! let mut context = Context::new(
+//!     settings::Rounding::Round(8),
+//!     settings::AngleUnit::Radian,
+//!     settings::DepthLimit::Limit(1000)
+//! );
+//! context.memoize = true;
+//!
+//! eval_with_mutable_context("f(n) = if(n < 2, n, f(n - 1) + f(n - 2))", &mut context).unwrap();
+//!
+//! assert_eq!(eval_with_static_context("f(25)", &context).unwrap(), Value::from(75025));
+//!
+//! // Cached results account for the variables read from the caller, also through
+//! // other functions
+//! eval_with_mutable_context("g(n) = k(n)", &mut context).unwrap();
+//! eval_with_mutable_context("k(x) = x + y", &mut context).unwrap();
+//! eval_with_mutable_context("h(y) = g(1)", &mut context).unwrap();
+//! assert_eq!(eval_with_static_context("h(1) + h(2)", &context).unwrap(), Value::from(5));
+//! ```
+//!
+//! ### Sharing between threads
+//!
+//! Contexts are `Send` and `Sync`, so a [`SharedContext`], that is an `Arc<Context>`, can
+//! be used to evaluate expressions from many threads with the same declarations:
+//!
+//! ```rust
+//! use num_parser2::*;
+//! use std::{sync::Arc, thread};
+//!
+//! fn assert_send_sync<T: Send + Sync>() {}
+//! assert_send_sync::<Context>();
+//! assert_send_sync::<Expression>();
+//!
+//! let context: SharedContext = Arc::new(Context::builder().variable("a", parse("2").unwrap()).build());
+//!
+//! let handle = {
+//!     let context = Arc::clone(&context);
+//!     thread::spawn(move || eval_with_static_context("a^10", &context).unwrap())
+//! };
+//! assert_eq!(handle.join().unwrap(), Value::from(1024));
 //! ```
 //!
 //! ### Serde
@@ -177,15 +651,63 @@
 //! num = { version = "<version>", features = [ "serde_support" ] }
 //! ```
 //!
+//! A whole context, with its declarations and settings, can then be saved and restored with
+//! any serde format. `Context::to_json` and `Context::from_json` do it with JSON in one call:
+//!
+//! ```rust,ignore
+//! let mut context = Context::default();
+//! context.define("f(x) = x^2 + 1").unwrap();
+//!
+//! let json = context.to_json().unwrap();
+//! let restored = Context::from_json(&json).unwrap();
+//!
+//! assert_eq!(eval_with_static_context("f(2)", &restored).unwrap(), Value::from(5));
+//! ```
+//!
+//! ### no_std
+//!
+//! The crate is `no_std` compatible. Disable the default `std` feature and enable `no_std`
+//! instead to use it with just `alloc`, for example on embedded devices:
+//!
+//! ```text
+//! [dependencies]
+//! num_parser2 = { version = "<version>", default-features = false, features = ["no_std"] }
+//! ```
+//!
+//! Parsing, evaluation and the angle conversions work the same way. The collections then come
+//! from `hashbrown` and the locks from `spin`, while the float functions are provided by
+//! `libm`; these dependencies are only pulled in by the `no_std` feature. One of `std` and
+//! `no_std` must be enabled. Since there is no source of entropy, the random functions use a
+//! seed of zero unless the context has one.
+//!
+//! ### WebAssembly
+//!
+//! The optional feature `wasm` exposes a `WasmContext` to JavaScript through
+//! [`wasm-bindgen`](https://docs.rs/wasm-bindgen). It keeps its declarations between
+//! evaluations, returns results as strings and throws errors as rendered messages:
+//!
+//! ```js
+//! const context = new WasmContext();
+//! context.define("f(x) = x^2 + 1");
+//!
+//! console.log(context.eval("f(3)")); // "10"
+//! ```
+//!
 //! ## License and contribution
 //! num_parser2 is licensed under a **MIT License**.
 //!
 //! Feel free to open issues and pull requests for any problems or ideas you come up with.
 //!
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "no_std")))]
+compile_error!("either the `std` or the `no_std` feature must be enabled");
+
 #[cfg(feature = "serde_support")]
 extern crate serde;
 
+extern crate alloc;
 extern crate num;
 
 pub mod function;
@@ -197,14 +719,22 @@ mod interpreter;
 mod objects;
 mod operators;
 mod out;
+mod prelude;
 mod token;
 mod tree;
 mod value;
 
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 pub use crate::{
     api::*,
-    context::{settings, Context},
-    objects::Expression,
+    context::{settings, Context, ContextBuilder, SharedContext},
+    objects::{CompiledExpression, Expression, Visitor},
     out::*,
+    token::{
+        tokentype::{IdentifierType, TokenType},
+        Token,
+    },
     value::{valuetype::*, Value},
 };
@@ -0,0 +1,228 @@
+use core::sync::atomic::Ordering;
+
+use crate::{
+    context::Context,
+    function::{builtin, Function},
+    objects::{binary_operation, unary_operation, Expression},
+    out::{ErrorType, EvalResult},
+    prelude::*,
+    token::tokentype::TokenType,
+    value::Value,
+};
+
+/// An expression lowered into a sequence of instructions, to be evaluated many times
+/// with different variable values. It is created with `Expression::compile`.
+///
+/// The context is captured when compiling, so later changes to it do not affect
+/// the compiled expression.
+#[derive(Clone)]
+pub struct CompiledExpression {
+    /// The instructions of a stack machine.
+    instructions: Vec<Instruction>,
+    /// The variables identifiers, by slot, with their value in the context, if any.
+    variables: Vec<(String, Option<Value>)>,
+    context: Context,
+}
+
+#[derive(Clone)]
+enum Instruction {
+    /// Pushes a value.
+    Push(Value),
+    /// Pushes the value of the variable in the slot.
+    Load(usize),
+    /// Pops two values and pushes the result of the operation.
+    Binary(TokenType),
+    /// Pops a value and pushes the result of the operation.
+    Unary(TokenType),
+    /// Pops the amount of values and pushes them as a vector.
+    Union(usize),
+    /// Pops the amount of arguments and pushes the result of the built-in function.
+    Call(Function, usize),
+    /// Pops the amount of arguments and pushes the result of the user-defined function.
+    CallUser(String, usize),
+    /// Pushes the result of an expression which cannot be lowered, like the calls to
+    /// functions evaluating their arguments lazily.
+    Eval(Expression),
+}
+
+impl Expression {
+    /// Compiles the expression to evaluate it many times with different values for
+    /// its variables. Variables declared in the context are used as default values.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut context = Context::default();
+    /// eval_script("a = 2; f(t) = t + 1", &mut context).unwrap();
+    ///
+    /// let compiled = parse("a*x^2 + f(y) + if(x > 0, 1, 1/0)").unwrap().compile(&context);
+    ///
+    /// let mut vars = HashMap::new();
+    /// for x in 1..=3 {
+    ///     vars.insert(String::from("x"), Value::from(x));
+    ///     vars.insert(String::from("y"), Value::from(10));
+    ///     assert_eq!(compiled.eval_compiled(&vars).unwrap(), Value::from(2 * x * x + 12));
+    /// }
+    ///
+    /// // Provided values shadow the context ones
+    /// vars.insert(String::from("a"), Value::from(0));
+    /// assert_eq!(compiled.eval_compiled(&vars).unwrap(), Value::from(12));
+    ///
+    /// vars.remove("y");
+    /// assert!(matches!(compiled.eval_compiled(&vars), Err(ErrorType::UnknownVar { .. })));
+    /// ```
+    pub fn compile(&self, context: &Context) -> CompiledExpression {
+        let mut context = context.clone();
+        context.steps = Arc::default();
+
+        let mut compiled = CompiledExpression {
+            instructions: vec![],
+            variables: vec![],
+            context,
+        };
+        compiled.lower(self);
+        compiled
+    }
+}
+
+impl CompiledExpression {
+    /// Appends the instructions computing the expression.
+    fn lower(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Binary(left_expr, token_type, right_expr) => {
+                self.lower(left_expr);
+                self.lower(right_expr);
+                self.instructions.push(Instruction::Binary(*token_type));
+            }
+            Expression::Unary(token_type, expr) => {
+                self.lower(expr);
+                self.instructions.push(Instruction::Unary(*token_type));
+            }
+            Expression::Union(expressions) => {
+                for expr in expressions {
+                    self.lower(expr);
+                }
+                self.instructions.push(Instruction::Union(expressions.len()));
+            }
+            Expression::Var(identifier) => {
+                let slot = match self.variables.iter().position(|(name, _)| name == identifier) {
+                    Some(slot) => slot,
+                    None => {
+                        let value = expression.eval(&self.context, None, 0).ok();
+                        self.variables.push((identifier.clone(), value));
+                        self.variables.len() - 1
+                    }
+                };
+                self.instructions.push(Instruction::Load(slot));
+            }
+            Expression::Literal(value) => self.instructions.push(Instruction::Push(value.clone())),
+            Expression::Func(identifier, arguments)
+                if !builtin::lazy_functions().contains(&&identifier[..]) =>
+            {
+                for argument in arguments {
+                    self.lower(argument);
+                }
+                self.instructions.push(match builtin::get_built_in_function(identifier) {
+                    Some(func) => Instruction::Call(func, arguments.len()),
+                    None => Instruction::CallUser(identifier.clone(), arguments.len()),
+                });
+            }
+            Expression::Func(_, _) => {
+                // Variables used by the expression are provided through the scope
+                for identifier in expression.variables() {
+                    self.lower(&Expression::Var(identifier.clone()));
+                    self.instructions.pop();
+                }
+                self.instructions.push(Instruction::Eval(expression.clone()));
+            }
+        }
+    }
+
+    /// Evaluates the compiled expression with the provided variables values. Any
+    /// other variable must have been declared in the context.
+    ///
+    /// As with the evaluation functions, the result is rounded according to the
+    /// context.
+    pub fn eval_compiled(&self, vars: &HashMap<String, Value>) -> EvalResult<Value> {
+        self.context.steps.store(0, Ordering::Relaxed);
+        self.context.memo.lock().unwrap().clear();
+
+        let variables = self
+            .variables
+            .iter()
+            .map(|(name, default)| vars.get(name).or(default.as_ref()))
+            .collect::<Vec<Option<&Value>>>();
+
+        let mut stack: Vec<Value> = vec![];
+        let pop = |stack: &mut Vec<Value>, amount: usize| -> EvalResult<Vec<Value>> {
+            match stack.len().checked_sub(amount) {
+                Some(start) => Ok(stack.split_off(start)),
+                None => Err(ErrorType::InternalError {
+                    message: String::from("missing values in compiled expression"),
+                }),
+            }
+        };
+
+        for instruction in &self.instructions {
+            let value = match instruction {
+                Instruction::Push(value) => value.clone(),
+                Instruction::Load(slot) => match variables[*slot] {
+                    Some(value) => value.clone(),
+                    None => {
+                        return Err(ErrorType::UnknownVar {
+                            var_name: self.variables[*slot].0.clone(),
+                        })
+                    }
+                },
+                Instruction::Binary(token_type) => {
+                    let mut operands = pop(&mut stack, 2)?.into_iter();
+                    let (left, right) = (operands.next().unwrap(), operands.next().unwrap());
+                    binary_operation(left, *token_type, right)?
+                }
+                Instruction::Unary(token_type) => {
+                    unary_operation(*token_type, pop(&mut stack, 1)?.remove(0))?
+                }
+                Instruction::Union(amount) => {
+                    let mut values = pop(&mut stack, *amount)?;
+                    match values.len() {
+                        1 => values.remove(0),
+                        _ => Value::Vector(values),
+                    }
+                }
+                Instruction::Call(func, amount) => {
+                    let arguments = literals(pop(&mut stack, *amount)?);
+                    func.call(&arguments, &self.context, None, 0)?
+                }
+                Instruction::CallUser(identifier, amount) => {
+                    Expression::Func(identifier.clone(), literals(pop(&mut stack, *amount)?))
+                        .eval_nested(&self.context, None, 0)?
+                }
+                Instruction::Eval(expression) => {
+                    let mut scope = Context::default();
+                    for ((name, _), value) in self.variables.iter().zip(&variables) {
+                        if let Some(value) = *value {
+                            scope.add_variable(name.clone(), Box::new(Expression::Literal(value.clone())));
+                        }
+                    }
+                    expression.eval_nested(&self.context, Some(&scope), 0)?
+                }
+            };
+            stack.push(value);
+        }
+
+        match pop(&mut stack, 1)?.pop() {
+            Some(value) => Ok(value.round(self.context.rounding)),
+            None => Ok(Value::Int(0)),
+        }
+    }
+}
+
+/// Wraps the values into literal expressions, to pass them as function arguments.
+#[allow(clippy::vec_box)]
+fn literals(values: Vec<Value>) -> Vec<Box<Expression>> {
+    values
+        .into_iter()
+        .map(|value| Box::new(Expression::Literal(value)))
+        .collect()
+}
@@ -0,0 +1,158 @@
+use crate::{
+    objects::Expression,
+    out::{ErrorType, EvalResult},
+    prelude::*,
+    token::tokentype::TokenType,
+    value::Value,
+};
+
+impl Expression {
+    /// Returns the derivative of the expression with respect to the variable, after
+    /// simplifying it. Any other variable is considered a constant.
+    ///
+    /// The sum, product, quotient and chain rules are applied to the arithmetic
+    /// operators and to the functions `sin`, `cos`, `tan`, `sinh`, `cosh`, `exp`, `ln`
+    /// and `sqrt`, assuming angles in radians. Any other operation depending on the
+    /// variable returns an error.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let derivative = |input| parse(input).unwrap().differentiate("x").unwrap().to_string();
+    ///
+    /// assert_eq!(derivative("x^2"), "2*x");
+    /// assert_eq!(derivative("3x + y"), "3");
+    /// assert_eq!(derivative("sin(x^2)"), "cos(x^2)*(2*x)");
+    /// assert_eq!(derivative("exp(2x)"), "exp(2*x)*2");
+    /// assert_eq!(derivative("ln(x)/x"), "(1/x*x - ln(x))/x^2");
+    /// assert_eq!(derivative("-cos(x)"), "-(-sin(x))");
+    ///
+    /// // The result can be evaluated
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("x = 2", &mut context).unwrap();
+    /// let result = parse("x^3 - sqrt(x)").unwrap().differentiate("x").unwrap();
+    /// assert_eq!(
+    ///     eval_with_static_context(&result.to_string(), &context).unwrap(),
+    ///     eval("12 - 1/(2sqrt(2))").unwrap()
+    /// );
+    ///
+    /// assert!(matches!(
+    ///     parse("floor(x)").unwrap().differentiate("x"),
+    ///     Err(ErrorType::NotDifferentiable { .. })
+    /// ));
+    /// // Unsupported operations not depending on the variable are constants
+    /// assert_eq!(derivative("floor(y) + x"), "1");
+    /// ```
+    pub fn differentiate(&self, var: &str) -> EvalResult<Expression> {
+        Ok(self.derivative(var)?.simplify())
+    }
+
+    /// Returns the derivative of the expression, without simplifying it.
+    fn derivative(&self, var: &str) -> EvalResult<Expression> {
+        if !self.variables().iter().any(|identifier| *identifier == var) {
+            return Ok(literal(0));
+        }
+
+        Ok(match self {
+            Self::Var(_) => literal(1),
+            Self::Binary(left, operator @ (TokenType::Plus | TokenType::Minus), right) => {
+                binary(left.derivative(var)?, *operator, right.derivative(var)?)
+            }
+            Self::Binary(left, TokenType::Star, right) => binary(
+                binary(left.derivative(var)?, TokenType::Star, *right.clone()),
+                TokenType::Plus,
+                binary(*left.clone(), TokenType::Star, right.derivative(var)?),
+            ),
+            Self::Binary(left, TokenType::Slash, right) => binary(
+                binary(
+                    binary(left.derivative(var)?, TokenType::Star, *right.clone()),
+                    TokenType::Minus,
+                    binary(*left.clone(), TokenType::Star, right.derivative(var)?),
+                ),
+                TokenType::Slash,
+                binary(*right.clone(), TokenType::Caret, literal(2)),
+            ),
+            Self::Binary(base, TokenType::Caret, exponent)
+                if !exponent.variables().iter().any(|identifier| *identifier == var) =>
+            {
+                // Power rule
+                binary(
+                    binary(
+                        *exponent.clone(),
+                        TokenType::Star,
+                        binary(
+                            *base.clone(),
+                            TokenType::Caret,
+                            binary(*exponent.clone(), TokenType::Minus, literal(1)),
+                        ),
+                    ),
+                    TokenType::Star,
+                    base.derivative(var)?,
+                )
+            }
+            Self::Binary(base, TokenType::Caret, exponent) => {
+                // (b^e)' = b^e * (e' * ln(b) + e * b' / b)
+                binary(
+                    self.clone(),
+                    TokenType::Star,
+                    binary(
+                        binary(exponent.derivative(var)?, TokenType::Star, func("ln", *base.clone())),
+                        TokenType::Plus,
+                        binary(
+                            binary(*exponent.clone(), TokenType::Star, base.derivative(var)?),
+                            TokenType::Slash,
+                            *base.clone(),
+                        ),
+                    ),
+                )
+            }
+            Self::Unary(TokenType::Minus, expr) => {
+                Self::Unary(TokenType::Minus, Box::new(expr.derivative(var)?))
+            }
+            Self::Func(identifier, arguments) if arguments.len() == 1 => {
+                let argument = *arguments[0].clone();
+                let outer = match &identifier[..] {
+                    "sin" => func("cos", argument.clone()),
+                    "cos" => Self::Unary(TokenType::Minus, Box::new(func("sin", argument.clone()))),
+                    "tan" => binary(
+                        literal(1),
+                        TokenType::Slash,
+                        binary(func("cos", argument.clone()), TokenType::Caret, literal(2)),
+                    ),
+                    "sinh" => func("cosh", argument.clone()),
+                    "cosh" => func("sinh", argument.clone()),
+                    "exp" => self.clone(),
+                    "ln" => binary(literal(1), TokenType::Slash, argument.clone()),
+                    "sqrt" => binary(
+                        literal(1),
+                        TokenType::Slash,
+                        binary(literal(2), TokenType::Star, self.clone()),
+                    ),
+                    _ => return Err(not_differentiable(self)),
+                };
+
+                // Chain rule
+                binary(outer, TokenType::Star, argument.derivative(var)?)
+            }
+            _ => return Err(not_differentiable(self)),
+        })
+    }
+}
+
+fn literal(value: i64) -> Expression {
+    Expression::Literal(Value::from(value))
+}
+
+fn binary(left: Expression, operator: TokenType, right: Expression) -> Expression {
+    Expression::Binary(Box::new(left), operator, Box::new(right))
+}
+
+fn func(identifier: &str, argument: Expression) -> Expression {
+    Expression::Func(String::from(identifier), vec![Box::new(argument)])
+}
+
+fn not_differentiable(expression: &Expression) -> ErrorType {
+    ErrorType::NotDifferentiable {
+        expression: expression.to_string(),
+    }
+}
@@ -1,9 +1,13 @@
-use std::fmt::Display;
+use core::fmt::{self, Display};
 
-use crate::objects::Request;
+use crate::{
+    objects::{Expression, Request},
+    prelude::*,
+    token::tokentype::TokenType,
+};
 
 impl Display for Request {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Evaluation(_) => write!(f, "evaluation"),
             Self::FuncDeclaration(_, _, _) => write!(f, "function declaration"),
@@ -11,3 +15,140 @@ impl Display for Request {
         }
     }
 }
+
+/// Writes the expression in infix notation, only adding the brackets required to
+/// keep the operators precedence. Binary operators with a precedence lower than or
+/// equal to the one of `+` are surrounded by spaces.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let expression = Expression::Binary(
+///     Box::new(Expression::Binary(
+///         Box::new(Expression::Literal(Value::from(2))),
+///         TokenType::Star,
+///         Box::new(Expression::Literal(Value::from(3))),
+///     )),
+///     TokenType::Plus,
+///     Box::new(Expression::Literal(Value::from(4))),
+/// );
+///
+/// assert_eq!(expression.to_string(), "2*3 + 4");
+///
+/// // Printing and parsing an expression gives back the same expression
+/// for input in [
+///     "-(x+1)^2 * sin x / 3!",
+///     "2 - (3 - x) - 4 >= (-2)!",
+///     "(2^3)^2 * max(1, 2)",
+///     "(n!)! * (20%) - 5",
+///     "7 % -3",
+/// ] {
+///     let expression = parse(input).unwrap();
+///     let printed = expression.to_string();
+///
+///     assert_eq!(parse(&printed).unwrap(), expression);
+/// }
+///
+/// assert_eq!(parse("-(x+1)^2 * sin x / 3!").unwrap().to_string(), "-(x + 1)^2*sin(x)/3!");
+/// assert_eq!(parse("2 - (3 - x) - 4").unwrap().to_string(), "2 - (3 - x) - 4");
+/// ```
+impl Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binary(left_expr, TokenType::Index, right_expr) => {
+                let precedence = TokenType::Index.precedence().unwrap_or_default();
+                write!(f, "{}[{}]", bracketed(left_expr, precedence, true), right_expr)
+            }
+            Self::Binary(left_expr, operator, right_expr) => {
+                let precedence = operator.precedence().unwrap_or_default();
+                let mut left = bracketed(left_expr, precedence, true);
+                let right = bracketed(right_expr, precedence, false);
+
+                // A percentage followed by `-` would be read as a modulo
+                if *operator == TokenType::Minus && left.ends_with('%') {
+                    left = format!("({})", left);
+                }
+
+                if precedence <= TokenType::Plus.precedence().unwrap_or_default() {
+                    write!(f, "{} {} {}", left, operator, right)
+                } else {
+                    write!(f, "{}{}{}", left, operator, right)
+                }
+            }
+            Self::Unary(operator @ (TokenType::Factorial | TokenType::Percent), expr) => {
+                let precedence = operator.precedence().unwrap_or_default();
+                write!(f, "{}{}", bracketed(expr, precedence, false), operator)
+            }
+            Self::Unary(operator, expr) => {
+                let precedence = operator.prefix_precedence().unwrap_or_default();
+                write!(f, "{}{}", operator, bracketed(expr, precedence, false))
+            }
+            Self::Var(identifier) => write!(f, "{}", identifier),
+            Self::Func(identifier, arguments) => write!(
+                f,
+                "{}({})",
+                identifier,
+                arguments
+                    .iter()
+                    .map(|argument| argument.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Self::Literal(value) => match value.as_vector() {
+                values if values.len() == 1 => write!(f, "{}", values[0]),
+                values if values.is_empty() => write!(f, "[]"),
+                values => write!(
+                    f,
+                    "({})",
+                    values
+                        .iter()
+                        .map(|value| value.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            },
+            Self::Union(expressions) => write!(
+                f,
+                "({})",
+                expressions
+                    .iter()
+                    .map(|expr| expr.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Returns the expression as a string, inside brackets if it would otherwise be
+/// parsed differently as an operand of an operator with the provided precedence.
+fn bracketed(expr: &Expression, precedence: u16, is_left: bool) -> String {
+    if needs_brackets(expr, precedence, is_left) {
+        format!("({})", expr)
+    } else {
+        expr.to_string()
+    }
+}
+
+/// Returns true if the expression would be parsed differently as an operand of an
+/// operator with the provided precedence, without brackets.
+pub(super) fn needs_brackets(expr: &Expression, precedence: u16, is_left: bool) -> bool {
+    match expr {
+        // Postfix operators can not follow each other, as `5!!` looks like a double factorial
+        Expression::Unary(TokenType::Factorial | TokenType::Percent, _) => {
+            precedence == TokenType::Factorial.precedence().unwrap_or_default()
+        }
+        // A prefix operation extends to the end of the expression
+        Expression::Unary(_, _) if !is_left => true,
+        Expression::Binary(_, operator, _) => {
+            let operator_precedence = operator.precedence().unwrap_or_default();
+            operator_precedence < precedence || (!is_left && operator_precedence == precedence)
+        }
+        Expression::Unary(operator, _) => {
+            operator.prefix_precedence().unwrap_or_default() < precedence
+        }
+        // Negative, rational and complex values
+        Expression::Literal(_) => expr.to_string().contains(['-', '+', '/']),
+        _ => false,
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    objects::{display::needs_brackets, Expression},
+    prelude::*,
+    token::tokentype::TokenType,
+    value::Value,
+};
+
+/// Functions with their own LaTeX command.
+const LATEX_FUNCTIONS: [&str; 20] = [
+    "sin", "cos", "tan", "cot", "sec", "csc", "sinh", "cosh", "tanh", "coth", "ln", "log", "exp",
+    "max", "min", "det", "gcd", "arg", "deg", "dim",
+];
+
+impl Expression {
+    /// Writes the expression in LaTeX. Divisions are written as fractions, square
+    /// roots and absolute values with their own notation, and brackets are only
+    /// added where needed to keep the operators precedence.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let latex = |input: &str| parse(input).unwrap().to_latex();
+    ///
+    /// assert_eq!(latex("(a + 1)/b"), r"\frac{a + 1}{b}");
+    /// assert_eq!(latex("x^2"), r"x^{2}");
+    /// assert_eq!(latex("(x + 1)^(y/2)"), r"\left(x + 1\right)^{\frac{y}{2}}");
+    /// assert_eq!(latex("sin(sqrt(x^2 + 1))"), r"\sin\left(\sqrt{x^{2} + 1}\right)");
+    /// assert_eq!(latex("2pi*abs(-x)"), r"2 \cdot \pi \cdot \left|-x\right|");
+    /// assert_eq!(latex("gamma(n + 1) <= n!"), r"\operatorname{gamma}\left(n + 1\right) \leq n!");
+    /// assert_eq!(latex("n // 2"), r"\left\lfloor \frac{n}{2} \right\rfloor");
+    /// assert_eq!(latex("f(x) = x - (1 - x)"), r"f\left(x\right) = x - \left(1 - x\right)");
+    /// ```
+    pub fn to_latex(&self) -> String {
+        match self {
+            Self::Binary(left_expr, TokenType::Slash, right_expr) => {
+                format!(r"\frac{{{}}}{{{}}}", left_expr.to_latex(), right_expr.to_latex())
+            }
+            Self::Binary(left_expr, TokenType::DoubleSlash, right_expr) => format!(
+                r"\left\lfloor \frac{{{}}}{{{}}} \right\rfloor",
+                left_expr.to_latex(),
+                right_expr.to_latex()
+            ),
+            Self::Binary(left_expr, TokenType::Caret, right_expr) => {
+                let precedence = TokenType::Caret.precedence().unwrap_or_default();
+                format!(
+                    "{}^{{{}}}",
+                    latex_bracketed(left_expr, precedence, true),
+                    right_expr.to_latex()
+                )
+            }
+            Self::Binary(left_expr, TokenType::Index, right_expr) => {
+                let precedence = TokenType::Index.precedence().unwrap_or_default();
+                format!(
+                    "{}_{{{}}}",
+                    latex_bracketed(left_expr, precedence, true),
+                    right_expr.to_latex()
+                )
+            }
+            Self::Binary(left_expr, operator, right_expr) => {
+                let precedence = operator.precedence().unwrap_or_default();
+                format!(
+                    "{} {} {}",
+                    latex_bracketed(left_expr, precedence, true),
+                    latex_operator(*operator),
+                    latex_bracketed(right_expr, precedence, false)
+                )
+            }
+            Self::Unary(TokenType::Factorial, expr) => {
+                let precedence = TokenType::Factorial.precedence().unwrap_or_default();
+                format!("{}!", latex_bracketed(expr, precedence, false))
+            }
+            Self::Unary(TokenType::Percent, expr) => {
+                let precedence = TokenType::Percent.precedence().unwrap_or_default();
+                format!(r"{}\%", latex_bracketed(expr, precedence, false))
+            }
+            Self::Unary(operator, expr) => {
+                let precedence = operator.prefix_precedence().unwrap_or_default();
+                format!("{}{}", latex_operator(*operator), latex_bracketed(expr, precedence, false))
+            }
+            Self::Var(identifier) => latex_identifier(identifier),
+            Self::Func(identifier, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| argument.to_latex())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                match &identifier[..] {
+                    "sqrt" => format!(r"\sqrt{{{}}}", arguments),
+                    "abs" => format!(r"\left|{}\right|", arguments),
+                    "asin" | "acos" | "atan" => {
+                        format!(r"\arc{}\left({}\right)", &identifier[1..], arguments)
+                    }
+                    name if LATEX_FUNCTIONS.contains(&name) => {
+                        format!(r"\{}\left({}\right)", name, arguments)
+                    }
+                    name if name.chars().count() == 1 => {
+                        format!(r"{}\left({}\right)", name, arguments)
+                    }
+                    name => format!(r"\operatorname{{{}}}\left({}\right)", name, arguments),
+                }
+            }
+            Self::Literal(value) => match value.as_vector() {
+                values if values.len() == 1 => latex_value(&values[0]),
+                values => format!(
+                    r"\left({}\right)",
+                    values.iter().map(latex_value).collect::<Vec<String>>().join(", ")
+                ),
+            },
+            Self::Union(expressions) => format!(
+                r"\left({}\right)",
+                expressions
+                    .iter()
+                    .map(|expr| expr.to_latex())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Returns the expression in LaTeX, inside brackets if it would otherwise be parsed
+/// differently as an operand of an operator with the provided precedence.
+fn latex_bracketed(expr: &Expression, precedence: u16, is_left: bool) -> String {
+    if needs_brackets(expr, precedence, is_left) {
+        format!(r"\left({}\right)", expr.to_latex())
+    } else {
+        expr.to_latex()
+    }
+}
+
+/// Returns the LaTeX symbol of an operator.
+fn latex_operator(operator: TokenType) -> String {
+    match operator {
+        TokenType::Star => String::from(r"\cdot"),
+        TokenType::Percentage => String::from(r"\bmod"),
+        TokenType::LessOrEqualTo => String::from(r"\leq"),
+        TokenType::GreaterOrEqualTo => String::from(r"\geq"),
+        TokenType::DoubleEqual => String::from("="),
+        TokenType::NotEqual => String::from(r"\neq"),
+        TokenType::DoubleLessThan => String::from(r"\ll"),
+        TokenType::DoubleGreaterThan => String::from(r"\gg"),
+        TokenType::DoubleAnd => String::from(r"\land"),
+        TokenType::DoubleOr => String::from(r"\lor"),
+        TokenType::And => String::from(r"\&"),
+        TokenType::Exclamation => String::from(r"\neg "),
+        other => other.to_string(),
+    }
+}
+
+/// Returns the LaTeX of an identifier: greek letters use their command, and longer
+/// identifiers are written upright.
+fn latex_identifier(identifier: &str) -> String {
+    match identifier {
+        "pi" | "π" => String::from(r"\pi"),
+        "tau" => String::from(r"\tau"),
+        "phi" => String::from(r"\phi"),
+        name if name.chars().count() == 1 => name.to_owned(),
+        name => format!(r"\mathrm{{{}}}", name.replace('_', r"\_")),
+    }
+}
+
+/// Returns the LaTeX of a value, writing fractions as such.
+fn latex_value(value: &Value) -> String {
+    match value {
+        Value::Rational(ratio) if *ratio.numer() < 0 => {
+            format!(r"-\frac{{{}}}{{{}}}", -ratio.numer(), ratio.denom())
+        }
+        Value::Rational(ratio) => format!(r"\frac{{{}}}{{{}}}", ratio.numer(), ratio.denom()),
+        other => other.to_string(),
+    }
+}
@@ -1,11 +1,20 @@
-use std::ops::Add;
+use core::sync::atomic::Ordering;
 
+mod compiled;
+mod derivative;
 mod display;
+mod latex;
+mod rpn;
+mod visitor;
+
+pub use self::compiled::CompiledExpression;
+pub use self::visitor::Visitor;
 
 use crate::{
     context::Context,
     function::builtin,
     out::{ErrorType, EvalResult},
+    prelude::*,
     settings,
     token::{
         self,
@@ -29,8 +38,8 @@ impl Request {
             }
             Self::FuncDeclaration(identifier, params, body) => {
                 if builtin::reserved_keywords().contains(&&identifier[..]) {
-                    Err(ErrorType::ReservedVarName {
-                        var_name: identifier.clone(),
+                    Err(ErrorType::ReservedFunctionName {
+                        func_name: identifier.clone(),
                     })
                 } else {
                     context.add_function(identifier.clone(), params.clone(), body.clone());
@@ -38,9 +47,10 @@ impl Request {
                 }
             }
             Self::VarDeclaration(identifier, expression) => {
-                if builtin::reserved_keywords().contains(&&identifier[..]) {
-                    Err(ErrorType::ReservedFunctionName {
-                        func_name: identifier.clone(),
+                // Variables can shadow built-in constants, but not functions
+                if builtin::get_built_in_function(identifier).is_some() {
+                    Err(ErrorType::ReservedVarName {
+                        var_name: identifier.clone(),
                     })
                 } else {
                     context.add_variable(identifier.clone(), expression.clone());
@@ -51,30 +61,537 @@ impl Request {
     }
 }
 
-/// Every expression variant.
+/// Every expression variant. Expressions are returned by `parse` as a tree, without
+/// being evaluated.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// match parse("1 + 2").unwrap() {
+///     Expression::Binary(left, TokenType::Plus, right) => {
+///         assert_eq!(*left, Expression::Literal(Value::from(1)));
+///         assert_eq!(*right, Expression::Literal(Value::from(2)));
+///     }
+///     other => panic!("unexpected expression {:?}", other),
+/// }
+///
+/// assert_eq!(
+///     parse("-sin(x, 2)").unwrap(),
+///     Expression::Unary(
+///         TokenType::Minus,
+///         Box::new(Expression::Func(
+///             String::from("sin"),
+///             vec![
+///                 Box::new(Expression::Var(String::from("x"))),
+///                 Box::new(Expression::Literal(Value::from(2))),
+///             ]
+///         ))
+///     )
+/// );
+/// ```
+///
+/// Equality between expressions is structural, not semantic: two expressions are
+/// equal only if they have the same tree, regardless of the whitespaces and brackets
+/// of their inputs.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// assert_eq!(parse("1 + 2").unwrap(), parse("1+2").unwrap());
+/// assert_eq!(parse("(1) + ((2))").unwrap(), parse("1 + 2").unwrap());
+/// assert_ne!(parse("1 + 2").unwrap(), parse("2 + 1").unwrap());
+/// assert_ne!(parse("x + x").unwrap(), parse("2x").unwrap());
+///
+/// // Displayed expressions parse back to the same tree
+/// let expression = parse("2x^2 - sin(x + 1)").unwrap();
+/// assert_eq!(parse(&expression.to_string()).unwrap(), expression);
+/// ```
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression {
-    /// A binary operation between two expression.
+    /// A binary operation between two expression: the left operand, the operator and
+    /// the right operand. Implicit multiplications are `TokenType::Star` operations,
+    /// and declarations are `TokenType::Equal` ones.
     Binary(Box<Expression>, TokenType, Box<Expression>),
-    /// An unary operation to an expression.
+    /// An unary operation to an expression, either prefix, like `TokenType::Minus`,
+    /// or postfix, like `TokenType::Factorial`.
     Unary(TokenType, Box<Expression>),
-    /// A variable.
+    /// A variable or constant, by its identifier. Functions passed as arguments, like
+    /// in `derivative(f, 2)`, are variables too.
     Var(String),
-    /// A function call and its parameters.
+    /// A function call, by its identifier, and its arguments.
     Func(String, Vec<Box<Expression>>),
     /// A literal value.
     Literal(Value),
-    /// A union of values.
+    /// A union of values, separated by commas, which evaluates to a vector.
     Union(Vec<Box<Expression>>),
 }
 
 impl Expression {
+    /// Returns the identifiers of all the variables used by the expression.
+    pub(crate) fn variables(&self) -> Vec<&String> {
+        match self {
+            Self::Binary(left_expr, _, right_expr) => [left_expr.variables(), right_expr.variables()].concat(),
+            Self::Unary(_, expr) => expr.variables(),
+            Self::Var(identifier) => vec![identifier],
+            Self::Func(_, expressions) | Self::Union(expressions) => {
+                expressions.iter().flat_map(|expr| expr.variables()).collect()
+            }
+            Self::Literal(_) => vec![],
+        }
+    }
+
+    /// Returns the depth of the expression tree, where a single value has depth `1`.
+    /// Brackets only group expressions, so they do not add depth.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(parse("1").unwrap().depth(), 1);
+    /// assert_eq!(parse("(((1)))").unwrap().depth(), 1);
+    /// assert_eq!(parse("1 + 2 + 3 + 4").unwrap().depth(), 4);
+    /// assert_eq!(parse("1 + 2 * 3").unwrap().depth(), 3);
+    /// assert_eq!(parse("sin(cos(tan(-x)))").unwrap().depth(), 5);
+    /// assert_eq!(parse("[1, 2, 3]").unwrap().depth(), 2);
+    /// ```
+    pub fn depth(&self) -> usize {
+        1 + self.children().iter().map(|child| child.depth()).max().unwrap_or(0)
+    }
+
+    /// Returns the amount of nodes of the expression tree.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(parse("(((1)))").unwrap().node_count(), 1);
+    /// assert_eq!(parse("1 + 2 + 3 + 4").unwrap().node_count(), 7);
+    /// assert_eq!(parse("max(1, 2, 3)").unwrap().node_count(), 4);
+    /// assert_eq!(parse("sin(cos(tan(-x)))").unwrap().node_count(), 5);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        1 + self.children().iter().map(|child| child.node_count()).sum::<usize>()
+    }
+
+    /// Returns the direct subexpressions.
+    fn children(&self) -> Vec<&Expression> {
+        match self {
+            Self::Binary(left_expr, _, right_expr) => vec![left_expr, right_expr],
+            Self::Unary(_, expr) => vec![expr],
+            Self::Func(_, expressions) | Self::Union(expressions) => {
+                expressions.iter().map(|expr| &**expr).collect()
+            }
+            Self::Var(_) | Self::Literal(_) => vec![],
+        }
+    }
+
+    /// Returns the identifiers of the variables which the expression needs to be
+    /// evaluated: those which are neither built-in constants nor declared in the
+    /// context, and are not bound by function parameters or by functions like `sum`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    /// use std::collections::HashSet;
+    ///
+    /// let names = |names: &[&str]| {
+    ///     names.iter().map(|name| name.to_string()).collect::<HashSet<String>>()
+    /// };
+    /// let mut context = Context::default();
+    ///
+    /// assert_eq!(parse("a + sin(b)").unwrap().free_variables(&context), names(&["a", "b"]));
+    /// assert_eq!(parse("2pi + e").unwrap().free_variables(&context), names(&[]));
+    ///
+    /// // Function parameters and bound variables shadow the name
+    /// assert_eq!(parse("f(x) = x + a").unwrap().free_variables(&context), names(&["a"]));
+    /// assert_eq!(parse("x + sum(x, 1, n, x^2)").unwrap().free_variables(&context), names(&["x", "n"]));
+    /// assert_eq!(parse("derivative(sqrt, t)").unwrap().free_variables(&context), names(&["t"]));
+    ///
+    /// // Declared variables are known
+    /// context.define("a = 2").unwrap();
+    /// assert_eq!(parse("a + sin(b)").unwrap().free_variables(&context), names(&["b"]));
+    /// ```
+    pub fn free_variables(&self, context: &Context) -> HashSet<String> {
+        let mut free = HashSet::new();
+        self.collect_free_variables(context, &mut vec![], &mut free);
+        free
+    }
+
+    /// Returns true if the expressions evaluate to the same values, within a small
+    /// tolerance, for random values of their free variables, taken between `-10` and
+    /// `10`. This is a probabilistic check, not a proof: expressions which only differ
+    /// at some points may be reported as equivalent. Points where both expressions
+    /// fail are ignored, but at least one point must be evaluated.
+    ///
+    /// Random values use the context seed, if any.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let context = Context::default();
+    /// let equivalent = |a: &str, b: &str| {
+    ///     parse(a).unwrap().is_equivalent(&parse(b).unwrap(), &context)
+    /// };
+    ///
+    /// assert!(equivalent("x + x", "2x"));
+    /// assert!(equivalent("(x + y)^2", "x^2 + 2xy + y^2"));
+    /// assert!(equivalent("sin(x)^2 + cos(x)^2", "1"));
+    /// assert!(equivalent("1/x", "x^-1"));
+    ///
+    /// assert!(!equivalent("x + 1", "x"));
+    /// assert!(!equivalent("x * y", "x + y"));
+    /// assert!(!equivalent("sqrt(x^2)", "x"));
+    /// assert!(!equivalent("1/0", "1/0"));
+    /// ```
+    pub fn is_equivalent(&self, other: &Expression, context: &Context) -> bool {
+        use rand::Rng;
+
+        const SAMPLES: usize = 20;
+
+        let mut names = self.free_variables(context);
+        names.extend(other.free_variables(context));
+
+        let mut evaluated = 0;
+        for _ in 0..SAMPLES {
+            let mut scope = Context::default();
+            for name in &names {
+                let value = context.with_rng(|rng| rng.gen_range(-10.0..10.0));
+                scope.add_variable(name.clone(), Box::new(Expression::Literal(Value::Float(value))));
+            }
+
+            match (self.eval(context, Some(&scope), 0), other.eval(context, Some(&scope), 0)) {
+                (Ok(left), Ok(right)) if approx_equal(&left, &right) => evaluated += 1,
+                (Err(_), Err(_)) => (),
+                _ => return false,
+            }
+        }
+        evaluated > 0
+    }
+
+    /// Adds the free variables of the expression to the set, ignoring the bound ones.
+    fn collect_free_variables<'a>(
+        &'a self,
+        context: &Context,
+        bound: &mut Vec<&'a str>,
+        free: &mut HashSet<String>,
+    ) {
+        match self {
+            Self::Var(identifier) => {
+                let is_known = bound.contains(&&identifier[..])
+                    || context.variables.contains_key(identifier)
+                    || context.functions.contains_key(identifier)
+                    || builtin::get_built_in_const(identifier).is_some()
+                    || builtin::get_built_in_function(identifier).is_some();
+                if !is_known {
+                    free.insert(identifier.clone());
+                }
+            }
+            // The declared identifier is not an occurrence, while function parameters
+            // are bound in the body
+            Self::Binary(declared, TokenType::Equal, body) => {
+                let bound_amount = bound.len();
+                if let Self::Func(_, params) = &**declared {
+                    for param in params {
+                        if let Self::Var(identifier) = &**param {
+                            bound.push(identifier);
+                        }
+                    }
+                }
+                body.collect_free_variables(context, bound, free);
+                bound.truncate(bound_amount);
+            }
+            Self::Binary(left_expr, _, right_expr) => {
+                left_expr.collect_free_variables(context, bound, free);
+                right_expr.collect_free_variables(context, bound, free);
+            }
+            Self::Unary(_, expr) => expr.collect_free_variables(context, bound, free),
+            // The first argument is either a bound variable or a function
+            Self::Func(identifier, arguments) if builtin::binding_functions().contains(&&identifier[..]) => {
+                let bound_amount = bound.len();
+                if let Some(Self::Var(variable)) = arguments.first().map(|argument| &**argument) {
+                    bound.push(variable);
+                }
+                for argument in arguments.iter().skip(1) {
+                    argument.collect_free_variables(context, bound, free);
+                }
+                bound.truncate(bound_amount);
+            }
+            Self::Func(_, expressions) | Self::Union(expressions) => {
+                for expr in expressions {
+                    expr.collect_free_variables(context, bound, free);
+                }
+            }
+            Self::Literal(_) => (),
+        }
+    }
+
+    /// Returns a copy of the expression where every free occurrence of the variable is
+    /// replaced by the provided expression. Occurrences bound by the parameters of a
+    /// function declaration, or by the variable of functions like `sum`, are kept.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let value = parse("y + 1").unwrap();
+    ///
+    /// assert_eq!(
+    ///     parse("2x^2 - sin(x)").unwrap().substitute("x", &value),
+    ///     parse("2(y + 1)^2 - sin(y + 1)").unwrap()
+    /// );
+    /// // Function parameters and bound variables shadow the name
+    /// assert_eq!(
+    ///     parse("f(x) = x + a").unwrap().substitute("x", &value),
+    ///     parse("f(x) = x + a").unwrap()
+    /// );
+    /// assert_eq!(
+    ///     parse("f(t) = x + t").unwrap().substitute("x", &value),
+    ///     parse("f(t) = (y + 1) + t").unwrap()
+    /// );
+    /// assert_eq!(
+    ///     parse("x + sum(x, 1, 3, x^2)").unwrap().substitute("x", &value),
+    ///     parse("(y + 1) + sum(x, 1, 3, x^2)").unwrap()
+    /// );
+    /// ```
+    pub fn substitute(&self, name: &str, value: &Expression) -> Expression {
+        let substitute_all = |expressions: &Vec<Box<Expression>>| {
+            expressions
+                .iter()
+                .map(|expr| Box::new(expr.substitute(name, value)))
+                .collect()
+        };
+
+        match self {
+            Self::Var(identifier) if identifier == name => value.clone(),
+            // The declared identifier and the function parameters are not occurrences
+            Self::Binary(declared, TokenType::Equal, body) => {
+                let is_shadowed = match &**declared {
+                    Self::Func(_, params) => params.iter().any(|param| param.is_var(name)),
+                    _ => false,
+                };
+
+                if is_shadowed {
+                    self.clone()
+                } else {
+                    Self::Binary(
+                        declared.clone(),
+                        TokenType::Equal,
+                        Box::new(body.substitute(name, value)),
+                    )
+                }
+            }
+            Self::Binary(left_expr, operator, right_expr) => Self::Binary(
+                Box::new(left_expr.substitute(name, value)),
+                *operator,
+                Box::new(right_expr.substitute(name, value)),
+            ),
+            Self::Unary(operator, expr) => Self::Unary(*operator, Box::new(expr.substitute(name, value))),
+            Self::Func(identifier, arguments)
+                if builtin::binding_functions().contains(&&identifier[..])
+                    && arguments.first().is_some_and(|argument| argument.is_var(name)) =>
+            {
+                self.clone()
+            }
+            Self::Func(identifier, arguments) => Self::Func(identifier.clone(), substitute_all(arguments)),
+            Self::Union(expressions) => Self::Union(substitute_all(expressions)),
+            Self::Var(_) | Self::Literal(_) => self.clone(),
+        }
+    }
+
+    /// Returns a simplified copy of the expression. Operations between literals are
+    /// replaced with their result, and the identities `x + 0`, `x - 0`, `x * 1`,
+    /// `x * 0`, `x / 1`, `x^1` and `x^0` are applied. Operations which would fail,
+    /// like `1/0`, are kept, and so are function calls, since they may depend on the
+    /// context.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let simplify = |input| parse(input).unwrap().simplify().to_string();
+    ///
+    /// assert_eq!(simplify("x + 0 + 2*0"), "x");
+    /// assert_eq!(simplify("0 + x"), "x");
+    /// assert_eq!(simplify("x - 0"), "x");
+    /// assert_eq!(simplify("1*x*1"), "x");
+    /// assert_eq!(simplify("0*sin(x)"), "0");
+    /// assert_eq!(simplify("x/1"), "x");
+    /// assert_eq!(simplify("x^1"), "x");
+    /// assert_eq!(simplify("x^(2 - 2)"), "1");
+    /// assert_eq!(simplify("3*4*x + 4/2"), "12*x + 2");
+    /// // Possibly zero divisors are kept
+    /// assert_eq!(simplify("0/x + 1/0"), "0/x + 1/0");
+    /// ```
+    pub fn simplify(&self) -> Expression {
+        let is_value = |expr: &Expression, n: i64| matches!(expr, Self::Literal(value) if *value == Value::from(n));
+
+        match self {
+            Self::Binary(left_expr, operator, right_expr) => {
+                let left = left_expr.simplify();
+                let right = right_expr.simplify();
+
+                match operator {
+                    TokenType::Plus if is_value(&left, 0) => right,
+                    TokenType::Plus | TokenType::Minus if is_value(&right, 0) => left,
+                    TokenType::Star if is_value(&left, 0) || is_value(&right, 0) => {
+                        Self::Literal(Value::from(0))
+                    }
+                    TokenType::Star if is_value(&left, 1) => right,
+                    TokenType::Star | TokenType::Slash | TokenType::Caret if is_value(&right, 1) => left,
+                    TokenType::Caret if is_value(&right, 0) => Self::Literal(Value::from(1)),
+                    _ => Self::Binary(Box::new(left), *operator, Box::new(right)).fold_literals(),
+                }
+            }
+            Self::Unary(operator, expr) => Self::Unary(*operator, Box::new(expr.simplify())).fold_literals(),
+            Self::Func(identifier, arguments) => Self::Func(
+                identifier.clone(),
+                arguments.iter().map(|expr| Box::new(expr.simplify())).collect(),
+            ),
+            Self::Union(expressions) => {
+                Self::Union(expressions.iter().map(|expr| Box::new(expr.simplify())).collect())
+            }
+            Self::Var(_) | Self::Literal(_) => self.clone(),
+        }
+    }
+
+    /// Replaces an operation whose operands are all literals with its result, if it
+    /// does not fail.
+    fn fold_literals(self) -> Expression {
+        let only_literals = match &self {
+            Self::Binary(left_expr, _, right_expr) => {
+                matches!((&**left_expr, &**right_expr), (Self::Literal(_), Self::Literal(_)))
+            }
+            Self::Unary(_, expr) => matches!(**expr, Self::Literal(_)),
+            _ => false,
+        };
+
+        match only_literals {
+            true => match self.eval(&Context::default(), None, 0) {
+                Ok(value) => Self::Literal(value),
+                Err(_) => self,
+            },
+            false => self,
+        }
+    }
+
+    /// Evaluates all the subexpressions which do not depend on unknown variables or
+    /// functions, replacing them with their value. Calls to functions like `sum`,
+    /// which bind a variable, are only replaced if they can be fully evaluated.
+    pub(crate) fn eval_partial(&self, context: &Context) -> EvalResult<Expression> {
+        match self.eval(context, None, 0) {
+            Ok(value) => return Ok(Self::Literal(value)),
+            Err(ErrorType::UnknownVar { .. } | ErrorType::UnknownFunction { .. }) => (),
+            Err(error) => return Err(error),
+        }
+
+        let eval_all = |expressions: &Vec<Box<Expression>>| -> EvalResult<Vec<Box<Expression>>> {
+            expressions
+                .iter()
+                .map(|expr| Ok(Box::new(expr.eval_partial(context)?)))
+                .collect()
+        };
+
+        Ok(match self {
+            Self::Binary(left_expr, operator, right_expr) => Self::Binary(
+                Box::new(left_expr.eval_partial(context)?),
+                *operator,
+                Box::new(right_expr.eval_partial(context)?),
+            ),
+            Self::Unary(operator, expr) => Self::Unary(*operator, Box::new(expr.eval_partial(context)?)),
+            Self::Func(identifier, arguments)
+                if !builtin::binding_functions().contains(&&identifier[..]) =>
+            {
+                Self::Func(identifier.clone(), eval_all(arguments)?)
+            }
+            Self::Union(expressions) => Self::Union(eval_all(expressions)?),
+            Self::Func(_, _) | Self::Var(_) | Self::Literal(_) => self.clone(),
+        })
+    }
+
+    /// Collects the identifiers of the variables which evaluating the expression may
+    /// read from the scope, following the bodies of the user-defined functions and
+    /// variables it uses. Identifiers which are not declared are split as during the
+    /// evaluation.
+    fn collect_scope_dependencies(
+        &self,
+        context: &Context,
+        visited: &mut HashSet<String>,
+        dependencies: &mut HashSet<String>,
+    ) {
+        let identifier = match self {
+            Self::Var(identifier) | Self::Func(identifier, _) => Some(identifier),
+            _ => None,
+        };
+        if let Some(identifier) = identifier {
+            dependencies.insert(identifier.clone());
+            let parts = match context.is_var(identifier) || context.is_function(identifier) {
+                true => vec![identifier.clone()],
+                false => token::split_into_identifiers(identifier.clone(), context)
+                    .into_iter()
+                    .map(|(part, _)| part)
+                    .collect(),
+            };
+            for part in parts {
+                dependencies.insert(part.clone());
+                if !visited.insert(part.clone()) {
+                    continue;
+                }
+                if let Some(expr) = context.get_var(&part) {
+                    expr.collect_scope_dependencies(context, visited, dependencies);
+                }
+                if let Some((_, body)) = context.get_function(&part) {
+                    body.collect_scope_dependencies(context, visited, dependencies);
+                }
+            }
+        }
+
+        for child in self.children() {
+            child.collect_scope_dependencies(context, visited, dependencies);
+        }
+    }
+
+    /// Returns true if the expression is the variable with the provided identifier.
+    fn is_var(&self, name: &str) -> bool {
+        matches!(self, Self::Var(identifier) if identifier == name)
+    }
+
+    /// Evaluates the expression, without rounding the result. Every call is a new
+    /// evaluation, so the steps counted for `step_limit` start from zero and results
+    /// cached by user-defined functions from previous calls are discarded.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let mut context = Context::default();
+    /// context.memoize = true;
+    /// eval_with_mutable_context("f(x) = 2x", &mut context).unwrap();
+    ///
+    /// let expression = parse("f(3)").unwrap();
+    /// assert_eq!(expression.eval(&context, None, 0).unwrap(), Value::from(6));
+    ///
+    /// eval_with_mutable_context("f(x) = 3x", &mut context).unwrap();
+    /// assert_eq!(expression.eval(&context, None, 0).unwrap(), Value::from(9));
+    ///
+    /// // The step limit applies to each evaluation
+    /// context.step_limit = Some(50);
+    /// let expression = parse("1 + 2 * 3").unwrap();
+    /// for _ in 0..100 {
+    ///     assert_eq!(expression.eval(&context, None, 0).unwrap(), Value::from(7));
+    /// }
+    /// ```
     pub fn eval(
         &self,
         context: &Context,
         scope: Option<&Context>,
         depth: u32,
+    ) -> EvalResult<Value> {
+        context.steps.store(0, Ordering::Relaxed);
+        context.memo.lock().unwrap().clear();
+        self.eval_nested(context, scope, depth)
+    }
+
+    /// Evaluates the expression as part of an evaluation which is already running.
+    pub(crate) fn eval_nested(
+        &self,
+        context: &Context,
+        scope: Option<&Context>,
+        depth: u32,
     ) -> EvalResult<Value> {
         let depth = depth + 1;
 
@@ -88,56 +605,26 @@ impl Expression {
             settings::DepthLimit::NoLimit => (),
         }
 
+        // Check step limit
+        if let Some(limit) = context.step_limit {
+            if context.steps.fetch_add(1, Ordering::Relaxed) as u64 >= limit {
+                return Err(ErrorType::StepLimitReached { limit });
+            }
+        }
+
         match self {
             Self::Binary(left_expr, token_type, right_expr) => {
-                let left_value = (**left_expr).eval(context, scope, depth)?;
-                let right_value = (**right_expr).eval(context, scope, depth)?;
-                Ok(match token_type {
-                    // Sum
-                    TokenType::Plus => (left_value + right_value)?,
-                    // Subtraction
-                    TokenType::Minus => (left_value - right_value)?,
-                    // Multiplication
-                    TokenType::Star => (left_value * right_value)?,
-                    // Division
-                    TokenType::Slash => (left_value / right_value)?,
-                    // Exponentiation
-                    TokenType::Caret => Value::exponentiation(left_value, right_value)?,
-                    // Modulo
-                    TokenType::Percentage => Value::modulo(left_value, right_value)?,
-                    // Less than
-                    TokenType::LessThan => Value::less_than(left_value, right_value)?,
-                    // Greater than
-                    TokenType::GreaterThan => Value::greater_than(left_value, right_value)?,
-                    // Less or equal to
-                    TokenType::LessOrEqualTo => Value::less_or_equal_to(left_value, right_value)?,
-                    // Greater or equal to
-                    TokenType::GreaterOrEqualTo => {
-                        Value::greater_or_equal_to(left_value, right_value)?
-                    }
-                    // Logical AND
-                    TokenType::DoubleAnd => Value::logical_and(left_value, right_value)?,
-                    // Logical OR
-                    TokenType::DoubleOr => Value::logical_or(left_value, right_value)?,
-                    // Equal to
-                    TokenType::DoubleEqual => Value::equal_to(left_value, right_value)?,
-                    // Not equal to
-                    TokenType::NotEqual => Value::not_equal_to(left_value, right_value)?,
-
-                    _ => return Err(ErrorType::InvalidTokenPosition { token: *token_type }),
-                })
+                let left_value = (**left_expr).eval_nested(context, scope, depth)?;
+                let right_value = (**right_expr).eval_nested(context, scope, depth)?;
+                binary_operation(left_value, *token_type, right_value)
+            }
+            Self::Unary(token_type, expr) => {
+                unary_operation(*token_type, expr.eval_nested(context, scope, depth)?)
             }
-            Self::Unary(token_type, expr) => Ok(match token_type {
-                // Negate
-                TokenType::Minus => Value::negate(expr.eval(context, scope, depth)?)?,
-                // Not
-                TokenType::Exclamation => Value::not(expr.eval(context, scope, depth)?)?,
-                _ => return Err(ErrorType::InvalidTokenPosition { token: *token_type }),
-            }),
             Self::Union(expressions) => {
                 let mut vec = vec![];
                 for expr in expressions {
-                    vec.push(expr.eval(context, scope, depth)?);
+                    vec.push(expr.eval_nested(context, scope, depth)?);
                 }
                 if vec.len() == 1 {
                     Ok(vec[0].clone())
@@ -146,21 +633,21 @@ impl Expression {
                 }
             }
             Self::Var(identifier) => {
-                // Check built-in vars
-                if let Some(var) = builtin::get_built_in_const(identifier) {
-                    return Ok(var);
-                }
-
-                // Check scope vars
+                // Check scope vars, which shadow any other
                 if let Some(c) = scope {
                     if let Some(expr) = c.get_var(identifier) {
-                        return Ok(expr.eval(context, scope, depth)?);
+                        return resolve_var(identifier, &expr, context, scope, depth);
                     }
                 }
 
                 // Check context
                 if let Some(expr) = context.get_var(identifier) {
-                    return Ok(expr.eval(context, scope, depth)?);
+                    return resolve_var(identifier, &expr, context, scope, depth);
+                }
+
+                // Check built-in vars
+                if let Some(var) = builtin::get_built_in_const(identifier) {
+                    return Ok(var);
                 }
 
                 // Try to split the identifier, as it might have not been interpreted correctly
@@ -195,21 +682,22 @@ impl Expression {
                             if let Some(func_ident) = argument {
                                 product = (product
                                     * Self::Func(func_ident, vec![Box::new(Self::Var(i))])
-                                        .eval(context, scope, depth)?)?;
+                                        .eval_nested(context, scope, depth)?)?;
                                 argument = Option::None;
                             } else {
-                                product = (product * Self::Var(i).eval(context, scope, depth)?)?;
+                                product = (product * Self::Var(i).eval_nested(context, scope, depth)?)?;
                             }
                         }
                     }
                 }
 
-                if valid && argument.is_none() {
-                    Ok(product)
-                } else {
-                    Err(ErrorType::UnknownVar {
+                match argument {
+                    None if valid => Ok(product),
+                    // A function reference used as a value
+                    Some(func_name) if valid => Err(ErrorType::MissingFunctionParameters { func_name }),
+                    _ => Err(ErrorType::UnknownVar {
                         var_name: identifier.clone(),
-                    })
+                    }),
                 }
             }
             Self::Func(identifier, arguments) => {
@@ -219,32 +707,32 @@ impl Expression {
                 }
                 // Check user-defined ones
                 if let Some((names, body)) = context.get_function(identifier) {
-                    let mut inner_scope = {
-                        let mut cont = context.clone();
-                        // Retrieve the parameters values
-                        let params = match value_to_params(
-                            names,
-                            &Expression::Union(arguments.clone()).eval(context, scope, depth)?,
-                        ) {
-                            Ok(value) => value,
-                            Err(err) => {
-                                return match err {
-                                    ErrorType::WrongFunctionArgumentsAmount {
-                                        func_name: _,
-                                        expected,
-                                        given,
-                                    } => Err(ErrorType::WrongFunctionArgumentsAmount {
-                                        func_name: identifier.clone(),
-                                        expected,
-                                        given,
-                                    }),
-                                    other => Err(other),
-                                }
+                    // Retrieve the parameters values
+                    let params = match value_to_params(
+                        names,
+                        &Expression::Union(arguments.clone()).eval_nested(context, scope, depth)?,
+                    ) {
+                        Ok(value) => value,
+                        Err(err) => {
+                            return match err {
+                                ErrorType::WrongFunctionArgumentsAmount {
+                                    func_name: _,
+                                    expected,
+                                    given,
+                                } => Err(ErrorType::WrongFunctionArgumentsAmount {
+                                    func_name: identifier.clone(),
+                                    expected,
+                                    given,
+                                }),
+                                other => Err(other),
                             }
-                        };
+                        }
+                    };
 
-                        for (name, val) in params {
-                            cont.add_variable(name, Box::new(Expression::Literal(val)))
+                    let mut inner_scope = {
+                        let mut cont = context.clone();
+                        for (name, val) in &params {
+                            cont.add_variable(name.clone(), Box::new(Expression::Literal(val.clone())))
                         }
 
                         cont
@@ -254,7 +742,31 @@ impl Expression {
                         inner_scope.join_with(cont);
                     }
 
-                    return body.eval(context, Some(&inner_scope), depth);
+                    if !context.memoize {
+                        return body.eval_nested(context, Some(&inner_scope), depth);
+                    }
+
+                    // The result also depends on the scope variables read by the body,
+                    // directly or through other functions and variables
+                    let mut dependencies = HashSet::new();
+                    body.collect_scope_dependencies(context, &mut HashSet::new(), &mut dependencies);
+                    let mut scope_vars = dependencies
+                        .into_iter()
+                        .filter(|identifier| !params.iter().any(|(name, _)| name == identifier))
+                        .filter_map(|identifier| {
+                            scope.and_then(|c| c.get_var(&identifier)).map(|expr| (identifier, expr))
+                        })
+                        .collect::<Vec<(String, Box<Expression>)>>();
+                    scope_vars.sort_by(|a, b| a.0.cmp(&b.0));
+                    let key = format!("{}{:?}{:?}", identifier, params, scope_vars);
+
+                    if let Some(value) = context.memo.lock().unwrap().get(&key) {
+                        return Ok(value.clone());
+                    }
+                    let value = body.eval_nested(context, Some(&inner_scope), depth)?;
+                    context.memo.lock().unwrap().insert(key, value.clone());
+
+                    return Ok(value);
                 }
 
                 // Try to split the identifier, as it might have not been interpreted correctly
@@ -295,11 +807,11 @@ impl Expression {
                                         func_ident,
                                         vec![Box::new(Self::Var(i.clone()))],
                                     )
-                                    .eval(context, scope, depth)?)?;
+                                    .eval_nested(context, scope, depth)?)?;
                                 argument = Option::None;
                             } else {
                                 product = (product
-                                    * Self::Var(i.clone()).eval(context, scope, depth)?)?;
+                                    * Self::Var(i.clone()).eval_nested(context, scope, depth)?)?;
                             }
                         }
                     }
@@ -310,7 +822,7 @@ impl Expression {
                 // the product of all the previous vars/functions and the result of the
                 // current one.
                 if valid && last_i_type == IdentifierType::Function {
-                    product * Self::Func(last_i, arguments.clone()).eval(context, scope, depth)?
+                    product * Self::Func(last_i, arguments.clone()).eval_nested(context, scope, depth)?
                 } else {
                     Err(ErrorType::UnknownFunction {
                         func_name: identifier.clone(),
@@ -322,6 +834,101 @@ impl Expression {
     }
 }
 
+/// Applies a binary operator to the two values.
+fn binary_operation(left_value: Value, token_type: TokenType, right_value: Value) -> EvalResult<Value> {
+    Ok(match token_type {
+        // Sum
+        TokenType::Plus => (left_value + right_value)?,
+        // Subtraction
+        TokenType::Minus => (left_value - right_value)?,
+        // Multiplication
+        TokenType::Star => (left_value * right_value)?,
+        // Division
+        TokenType::Slash => (left_value / right_value)?,
+        // Floor division
+        TokenType::DoubleSlash => Value::floor_division(left_value, right_value)?,
+        // Exponentiation
+        TokenType::Caret => Value::exponentiation(left_value, right_value)?,
+        // Modulo
+        TokenType::Percentage => Value::modulo(left_value, right_value)?,
+        // Index
+        TokenType::Index => Value::index(left_value, right_value)?,
+        // Less than
+        TokenType::LessThan => Value::less_than(left_value, right_value)?,
+        // Greater than
+        TokenType::GreaterThan => Value::greater_than(left_value, right_value)?,
+        // Less or equal to
+        TokenType::LessOrEqualTo => Value::less_or_equal_to(left_value, right_value)?,
+        // Greater or equal to
+        TokenType::GreaterOrEqualTo => {
+            Value::greater_or_equal_to(left_value, right_value)?
+        }
+        // Bitwise AND
+        TokenType::And => Value::bitwise_and(left_value, right_value)?,
+        // Bitwise OR
+        TokenType::Or => Value::bitwise_or(left_value, right_value)?,
+        // Left shift
+        TokenType::DoubleLessThan => Value::left_shift(left_value, right_value)?,
+        // Right shift
+        TokenType::DoubleGreaterThan => Value::right_shift(left_value, right_value)?,
+        // Logical AND
+        TokenType::DoubleAnd => Value::logical_and(left_value, right_value)?,
+        // Logical OR
+        TokenType::DoubleOr => Value::logical_or(left_value, right_value)?,
+        // Equal to
+        TokenType::DoubleEqual => Value::equal_to(left_value, right_value)?,
+        // Not equal to
+        TokenType::NotEqual => Value::not_equal_to(left_value, right_value)?,
+
+        _ => return Err(ErrorType::InvalidTokenPosition { token: token_type }),
+    })
+}
+
+/// Applies a unary operator to the value.
+fn unary_operation(token_type: TokenType, value: Value) -> EvalResult<Value> {
+    Ok(match token_type {
+        // Negate
+        TokenType::Minus => Value::negate(value)?,
+        // Not
+        TokenType::Exclamation => Value::not(value)?,
+        // Factorial
+        TokenType::Factorial => Value::factorial(value)?,
+        // Percentage
+        TokenType::Percent => (value / Value::Int(100))?,
+        _ => return Err(ErrorType::InvalidTokenPosition { token: token_type }),
+    })
+}
+
+/// Evaluates the expression of a variable. A variable which is read again while it is
+/// being resolved depends on itself, so `ErrorType::CircularReference` is returned
+/// before the recursion reaches the depth limit.
+fn resolve_var(
+    identifier: &str,
+    expr: &Expression,
+    context: &Context,
+    scope: Option<&Context>,
+    depth: u32,
+) -> EvalResult<Value> {
+    // Literals, like function parameters, do not depend on anything
+    if let Expression::Literal(_) = expr {
+        return expr.eval_nested(context, scope, depth);
+    }
+
+    {
+        let mut resolving = context.resolving.lock().unwrap();
+        if let Some(position) = resolving.iter().position(|name| name == identifier) {
+            return Err(ErrorType::CircularReference {
+                names: resolving[position..].to_vec(),
+            });
+        }
+        resolving.push(identifier.to_owned());
+    }
+    let result = expr.eval_nested(context, scope, depth);
+    context.resolving.lock().unwrap().pop();
+
+    result
+}
+
 fn value_to_params(names: Vec<String>, value: &Value) -> EvalResult<Vec<(String, Value)>> {
     match value {
         Value::Vector(vec) => {
@@ -332,7 +939,7 @@ fn value_to_params(names: Vec<String>, value: &Value) -> EvalResult<Vec<(String,
                     given: vec.len() as u8,
                 })
             } else {
-                let zipped = std::iter::zip(names, vec);
+                let zipped = core::iter::zip(names, vec);
                 let mut out = vec![];
                 for (name, val) in zipped {
                     out.push((name, val.clone()));
@@ -353,3 +960,18 @@ fn value_to_params(names: Vec<String>, value: &Value) -> EvalResult<Vec<(String,
         }
     }
 }
+
+/// Returns true if the values are equal within a relative tolerance. Vectors are
+/// compared element-wise.
+fn approx_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Vector(_), _) | (_, Value::Vector(_)) => {
+            let (left, right) = (left.as_vector(), right.as_vector());
+            left.len() == right.len() && left.iter().zip(&right).all(|(l, r)| approx_equal(l, r))
+        }
+        _ => match (left.as_complex(), right.as_complex()) {
+            (Ok(l), Ok(r)) => (l - r).norm() <= 1e-9 * l.norm().max(r.norm()).max(1.0),
+            _ => false,
+        },
+    }
+}
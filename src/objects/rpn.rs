@@ -0,0 +1,68 @@
+use crate::{
+    function::{builtin::get_built_in_function, Arguments},
+    objects::Expression,
+    prelude::*,
+    token::tokentype::TokenType,
+};
+
+impl Expression {
+    /// Writes the expression in reverse Polish notation, with the tokens separated by
+    /// spaces, so that `parse_rpn` gives back the same expression. Negations are written
+    /// as `neg` and logical negations as `not`.
+    ///
+    /// Indexing, percentages, unions and calls of functions which are not built-in, or which take any
+    /// amount of arguments, have no postfix form: they are written in infix notation as
+    /// a single token.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(parse("2*3 + 4").unwrap().to_rpn(), "2 3 * 4 +");
+    /// assert_eq!(parse("-(x + 1)^2/3!").unwrap().to_rpn(), "x 1 + 2 ^ neg 3 ! /");
+    /// assert_eq!(parse("atan2(sin(x), 2) + max(x, 1)").unwrap().to_rpn(), "x sin 2 atan2 max(x,1) +");
+    ///
+    /// // Writing and parsing an expression gives back the same expression
+    /// for input in [
+    ///     "2*3 + 4",
+    ///     "-(x+1)^2 * sin x / 3!",
+    ///     "2 - (3 - x) - 4 >= (-2)!",
+    ///     "(2^3)^2 * max(1, 2 + x)",
+    ///     "!(x < 1) || x[0] == [1, 2]",
+    ///     "sqrt(re(3 + 4i)) % log(100, 10) // 2",
+    /// ] {
+    ///     let expression = parse(input).unwrap();
+    ///     assert_eq!(parse_rpn(&expression.to_rpn()).unwrap(), expression);
+    /// }
+    /// ```
+    pub fn to_rpn(&self) -> String {
+        match self {
+            Self::Binary(_, TokenType::Index, _) | Self::Unary(TokenType::Percent, _) => {
+                infix_token(self)
+            }
+            Self::Binary(left_expr, operator, right_expr) => {
+                format!("{} {} {}", left_expr.to_rpn(), right_expr.to_rpn(), operator)
+            }
+            Self::Unary(TokenType::Minus, expr) => format!("{} neg", expr.to_rpn()),
+            Self::Unary(TokenType::Exclamation, expr) => format!("{} not", expr.to_rpn()),
+            Self::Unary(operator, expr) => format!("{} {}", expr.to_rpn(), operator),
+            Self::Func(identifier, arguments) => match get_built_in_function(identifier) {
+                Some(function) if function.args == Arguments::Const(arguments.len()) => {
+                    let mut tokens = arguments
+                        .iter()
+                        .map(|argument| argument.to_rpn())
+                        .collect::<Vec<String>>();
+                    tokens.push(identifier.clone());
+                    tokens.join(" ")
+                }
+                _ => infix_token(self),
+            },
+            Self::Var(_) | Self::Literal(_) | Self::Union(_) => infix_token(self),
+        }
+    }
+}
+
+/// Returns the expression in infix notation without whitespaces, so that it is a
+/// single token.
+fn infix_token(expr: &Expression) -> String {
+    expr.to_string().replace(' ', "")
+}
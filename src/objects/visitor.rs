@@ -0,0 +1,93 @@
+use crate::{objects::Expression, prelude::*, token::tokentype::TokenType, value::Value};
+
+/// Visits the nodes of an expression tree, through `Expression::accept`. Every method
+/// is called once for each node of its kind, before its children are visited, and
+/// does nothing by default, so visitors only implement the ones they need.
+///
+/// ## Examples
+/// ```
+/// use num_parser2::*;
+///
+/// /// Counts the function calls.
+/// #[derive(Default)]
+/// struct FunctionCounter {
+///     calls: usize,
+/// }
+///
+/// impl Visitor for FunctionCounter {
+///     fn visit_func(&mut self, _identifier: &str, _arguments: &[Box<Expression>]) {
+///         self.calls += 1;
+///     }
+/// }
+///
+/// let mut counter = FunctionCounter::default();
+/// parse("sin(cos(x)) + max(1, sqrt(4)) * 2").unwrap().accept(&mut counter);
+/// assert_eq!(counter.calls, 4);
+///
+/// /// Collects the variables, in order.
+/// #[derive(Default)]
+/// struct VarCollector {
+///     identifiers: Vec<String>,
+/// }
+///
+/// impl Visitor for VarCollector {
+///     fn visit_var(&mut self, identifier: &str) {
+///         self.identifiers.push(identifier.to_owned());
+///     }
+/// }
+///
+/// let mut collector = VarCollector::default();
+/// parse("x^2 + sin(y) - [z, -x]").unwrap().accept(&mut collector);
+/// assert_eq!(collector.identifiers, vec!["x", "y", "z", "x"]);
+/// ```
+pub trait Visitor {
+    /// Visits a binary operation.
+    fn visit_binary(&mut self, _left: &Expression, _operator: TokenType, _right: &Expression) {}
+
+    /// Visits an unary operation.
+    fn visit_unary(&mut self, _operator: TokenType, _expression: &Expression) {}
+
+    /// Visits a variable.
+    fn visit_var(&mut self, _identifier: &str) {}
+
+    /// Visits a function call.
+    fn visit_func(&mut self, _identifier: &str, _arguments: &[Box<Expression>]) {}
+
+    /// Visits a literal value.
+    fn visit_literal(&mut self, _value: &Value) {}
+
+    /// Visits a union of values.
+    fn visit_union(&mut self, _elements: &[Box<Expression>]) {}
+}
+
+impl Expression {
+    /// Traverses the expression tree, calling the visitor methods for every node
+    /// before visiting its children, from left to right.
+    pub fn accept(&self, visitor: &mut impl Visitor) {
+        match self {
+            Self::Binary(left_expr, token_type, right_expr) => {
+                visitor.visit_binary(left_expr, *token_type, right_expr);
+                left_expr.accept(visitor);
+                right_expr.accept(visitor);
+            }
+            Self::Unary(token_type, expr) => {
+                visitor.visit_unary(*token_type, expr);
+                expr.accept(visitor);
+            }
+            Self::Var(identifier) => visitor.visit_var(identifier),
+            Self::Func(identifier, arguments) => {
+                visitor.visit_func(identifier, arguments);
+                for argument in arguments {
+                    argument.accept(visitor);
+                }
+            }
+            Self::Literal(value) => visitor.visit_literal(value),
+            Self::Union(expressions) => {
+                visitor.visit_union(expressions);
+                for expr in expressions {
+                    expr.accept(visitor);
+                }
+            }
+        }
+    }
+}
@@ -1,16 +1,35 @@
 use crate::{
     out::{ErrorType, EvalResult},
-    value::{valuetype::ValueType, Value},
+    prelude::*,
+    value::{valuetype::ValueType, ComplexValue, IntValue, RationalValue, Value},
 };
-use std::ops::Add;
-use std::ops::Not;
-use std::ops::Sub;
-use std::ops::Mul;
-use std::ops::Div;
+use num::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Zero};
+use core::ops::Add;
+use core::ops::Div;
+use core::ops::Mul;
+use core::ops::Neg;
+use core::ops::Rem;
+use core::ops::Sub;
 
 // Implement operators for values. The values should be converted
 // to the highest complex type of the operands.
 
+/// Applies an exact operation if both values are rationals, falling back to the
+/// complex one if any of them is not or if the exact operation overflows.
+fn rational_or_complex(
+    lhs: Value,
+    rhs: Value,
+    exact: fn(&RationalValue, &RationalValue) -> Option<RationalValue>,
+    complex: fn(ComplexValue, ComplexValue) -> ComplexValue,
+) -> EvalResult<Value> {
+    if let (Value::Rational(l), Value::Rational(r)) = (&lhs, &rhs) {
+        if let Some(result) = exact(l, r) {
+            return Ok(Value::from(result));
+        }
+    }
+    Ok(Value::Complex(complex(lhs.as_complex()?, rhs.as_complex()?)))
+}
+
 /// Convert values to a valid value and apply the operation.
 fn convert_and_apply<T>(
     lhs: &Value,
@@ -87,10 +106,21 @@ where
     }
 
     if lhs_as_vector.len() == 1 && rhs_as_vector.len() == 1 {
-        // Convert the values to the right type
+        // Convert the values to the target type, or to the operands type if it
+        // is more complex.
+        let target_value_type =
+            ValueType::highest_complexity(vec![&target_value_type, &highest_complexity]);
         let lhs_converted = lhs.as_type(&target_value_type)?;
         let rhs_converted = rhs.as_type(&target_value_type)?;
 
+        // Bools are treated as 0 and 1 by non-logical operations, whose results
+        // should therefore never be cast back into bools.
+        let highest_complexity = if target_value_type == ValueType::BoolType {
+            highest_complexity
+        } else {
+            ValueType::highest_complexity(vec![&highest_complexity, &ValueType::IntType])
+        };
+
         if inverse {
             Ok(operation(rhs_converted, lhs_converted)?.try_as_type(highest_complexity))
         } else {
@@ -143,15 +173,73 @@ where
     }
 }
 
+/// Raises a rational to an integer power by squaring, returning `None` on overflow.
+fn rational_pow(base: &RationalValue, exponent: IntValue) -> Option<RationalValue> {
+    let mut result = RationalValue::from_integer(1);
+    let mut power = *base;
+    let mut remaining = exponent.unsigned_abs();
+
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            result = result.checked_mul(&power)?;
+        }
+        remaining >>= 1;
+        if remaining > 0 {
+            power = power.checked_mul(&power)?;
+        }
+    }
+
+    if exponent < 0 {
+        RationalValue::from_integer(1).checked_div(&result)
+    } else {
+        Some(result)
+    }
+}
+
+/// Returns a valid shift amount, which must be in the `0..64` range.
+fn shift_amount(value: &Value, operation_name: &'static str) -> EvalResult<u32> {
+    let amount = value.as_int()?;
+    if (0..IntValue::BITS as IntValue).contains(&amount) {
+        Ok(amount as u32)
+    } else {
+        Err(ErrorType::DomainError {
+            value: value.clone(),
+            operation_name,
+        })
+    }
+}
+
+/// Arithmetic operators return an `EvalResult`, so that operations which may fail,
+/// like a division by zero, report the same errors as the evaluation functions and
+/// can be chained with `?`.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// fn hypotenuse_squared(a: Value, b: Value) -> EvalResult<Value> {
+///     (a.clone() * a)? + (b.clone() * b)?
+/// }
+///
+/// assert_eq!(hypotenuse_squared(Value::from(3), Value::from(4)).unwrap(), Value::from(25));
+/// assert_eq!((-Value::from(2)).unwrap(), Value::from(-2));
+/// assert_eq!((Value::from(7) % Value::from(4)).unwrap(), Value::from(3));
+/// assert!(matches!(Value::from(1) % Value::from(0), Err(ErrorType::DivideByZero { .. })));
+/// assert!(matches!(
+///     Value::from(vec![1, 2]) + Value::from(vec![1, 2, 3]),
+///     Err(ErrorType::MismatchedArrayLengths { .. })
+/// ));
+/// ```
 impl Add<Self> for Value {
     type Output = EvalResult<Self>;
     fn add(self, rhs: Self) -> EvalResult<Self> {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? + rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                rational_or_complex(lhs, rhs, RationalValue::checked_add, |l, r| l + r)
+            },
             "Sum",
-            ValueType::ComplexType,
+            ValueType::RationalType,
             false,
         )
     }
@@ -164,9 +252,11 @@ impl Sub<Self> for Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? - rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                rational_or_complex(lhs, rhs, RationalValue::checked_sub, |l, r| l - r)
+            },
             "Subtraction",
-            ValueType::ComplexType,
+            ValueType::RationalType,
             false,
         )
     }
@@ -178,14 +268,30 @@ impl Mul<Self> for Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? * rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                rational_or_complex(lhs, rhs, RationalValue::checked_mul, |l, r| l * r)
+            },
             "Multiplication",
-            ValueType::ComplexType,
+            ValueType::RationalType,
             false,
         )
     }
 }
 
+/// Divides two values. Real operands are promoted to complex ones, so that
+/// dividing by any kind of zero returns `ErrorType::DivideByZero`.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// assert_eq!((Value::from(1) / Value::from(4)).unwrap(), Value::from(0.25));
+/// assert_eq!(eval("3e200 / 1e200").unwrap(), Value::from(3));
+/// assert!(matches!(
+///     Value::from(3) / Value::Complex(num::complex::Complex64::new(0.0, 0.0)),
+///     Err(ErrorType::DivideByZero { .. })
+/// ));
+/// assert!(matches!(eval("2i/0"), Err(ErrorType::DivideByZero { .. })));
+/// ```
 impl Div<Self> for Value {
     type Output = EvalResult<Self>;
 
@@ -193,51 +299,225 @@ impl Div<Self> for Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? / rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                let denominator = rhs.as_complex()?;
+                if denominator.is_zero() {
+                    return Err(ErrorType::DivideByZero {
+                        numerator: lhs.try_as_type(ValueType::IntType),
+                    });
+                }
+                rational_or_complex(lhs, rhs, RationalValue::checked_div, |l, r| {
+                    // Avoid squaring the denominator, which overflows for large reals
+                    if l.im == 0.0 && r.im == 0.0 {
+                        ComplexValue::new(l.re / r.re, 0.0)
+                    } else {
+                        l / r
+                    }
+                })
+            },
             "Division",
-            ValueType::ComplexType,
+            ValueType::RationalType,
             false,
         )
     }
 }
 
+impl Rem<Self> for Value {
+    type Output = EvalResult<Self>;
+
+    fn rem(self, rhs: Self) -> EvalResult<Self> {
+        self.modulo(rhs)
+    }
+}
+
+impl Neg for Value {
+    type Output = EvalResult<Self>;
+
+    fn neg(self) -> EvalResult<Self> {
+        self.negate()
+    }
+}
+
 impl Value {
     pub fn negate(self) -> EvalResult<Self> {
         let zero = Value::Int(0);
         convert_and_apply(
             &zero,
             &self,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? - rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                rational_or_complex(lhs, rhs, RationalValue::checked_sub, |l, r| l - r)
+            },
             "Negation",
-            ValueType::ComplexType,
+            ValueType::RationalType,
             false,
         )
     }
 
     pub fn exponentiation(self, rhs: Self) -> EvalResult<Self> {
+        self.pow(rhs)
+    }
+
+    /// Raises the value to a power. Integer and rational values raised to an integer
+    /// power are computed exactly, falling back to floats if the result overflows.
+    /// Negative real values raised to a non-integer power return the principal
+    /// complex value.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(Value::from(2).pow(Value::from(10)).unwrap(), Value::Int(1024));
+    /// assert_eq!(Value::from(2).pow(Value::from(-2)).unwrap(), Value::from(0.25));
+    /// assert_eq!(Value::from(-3).pow(Value::from(3)).unwrap(), Value::Int(-27));
+    /// assert_eq!(Value::from(2).pow(Value::from(0.5)).unwrap(), Value::from(2f64.sqrt()));
+    /// assert_eq!(Value::from(2).pow(Value::from(100)).unwrap(), Value::from(2f64.powi(100)));
+    /// assert!(Value::from(-8).pow(Value::from(1.0 / 3.0)).unwrap().is_complex());
+    /// assert!(matches!(
+    ///     Value::from(0).pow(Value::from(-1)),
+    ///     Err(ErrorType::DivideByZero { .. })
+    /// ));
+    /// ```
+    pub fn pow(self, exp: Self) -> EvalResult<Self> {
         convert_and_apply(
             &self,
-            &rhs,
+            &exp,
             &mut |lhs, rhs| {
-                let lhs_as_complex = lhs.as_complex()?;
-                let rhs_as_complex = rhs.as_complex()?;
+                if let (Value::Rational(base), Value::Rational(exponent)) = (&lhs, &rhs) {
+                    if exponent.is_integer() {
+                        let exponent = exponent.to_integer();
+                        if base.is_zero() && exponent < 0 {
+                            return Err(ErrorType::DivideByZero {
+                                numerator: Value::Int(1),
+                            });
+                        }
+                        if let Some(result) = rational_pow(base, exponent) {
+                            return Ok(Value::from(result));
+                        }
+                    }
+                }
 
-                // a^b = e^(b*ln(a))
-                Ok(Value::Complex((rhs_as_complex * lhs_as_complex.ln()).exp()))
+                match (lhs.as_f64(), rhs.as_f64()) {
+                    (Some(base), Some(exponent)) if base >= 0.0 || exponent.fract() == 0.0 => {
+                        Ok(Value::Float(base.powf(exponent)))
+                    }
+                    _ => {
+                        let lhs_as_complex = lhs.as_complex()?;
+                        let rhs_as_complex = rhs.as_complex()?;
+
+                        // a^b = e^(b*ln(a))
+                        Ok(Value::Complex((rhs_as_complex * lhs_as_complex.ln()).exp()))
+                    }
+                }
             },
             "Exponentiation",
-            ValueType::ComplexType,
+            ValueType::RationalType,
+            false,
+        )
+    }
+
+    /// Returns the square root of the value. Since complex numbers are supported,
+    /// the square root of a negative number is an imaginary number.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(Value::from(2).sqrt().unwrap(), Value::from(1.4142135623730951));
+    /// assert_eq!(Value::from(16).sqrt().unwrap(), Value::Int(4));
+    /// assert_eq!(Value::from(-4).sqrt().unwrap(), eval("2i").unwrap());
+    /// assert_eq!(Value::from(vec![4, 9]).sqrt().unwrap(), Value::from(vec![2, 3]));
+    /// ```
+    pub fn sqrt(self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &Value::Int(0),
+            &mut |lhs, _| match lhs.as_f64() {
+                Some(x) if x >= 0.0 => Ok(Value::Float(x.sqrt())),
+                Some(x) => Ok(Value::Complex(ComplexValue::new(0.0, (-x).sqrt()))),
+                None => Ok(Value::Complex(lhs.as_complex()?.sqrt())),
+            },
+            "Square root",
+            ValueType::FloatType,
             false,
         )
     }
 
+    /// Returns the quotient of the division of two values rounded toward negative
+    /// infinity, as an integer. Dividing by zero returns `ErrorType::DivideByZero`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("7 // 2").unwrap(), Value::Int(3));
+    /// assert_eq!(eval("(-7) // 2").unwrap(), Value::Int(-4));
+    /// assert_eq!(eval("7 // (-2)").unwrap(), Value::Int(-4));
+    /// assert_eq!(eval("(-7) // (-2)").unwrap(), Value::Int(3));
+    /// // The negation binds tighter than the division
+    /// assert_eq!(eval("-7 // 2").unwrap(), Value::Int(-4));
+    /// assert_eq!(eval("-(7 // 2)").unwrap(), Value::Int(-3));
+    /// assert_eq!(eval("6 // 3").unwrap(), Value::Int(2));
+    /// assert_eq!(eval("7.5 // 2").unwrap(), Value::Int(3));
+    /// assert_eq!(eval("(-0.5) // 2").unwrap(), Value::Int(-1));
+    /// assert_eq!(eval("(7/2) // (1/3)").unwrap(), Value::Int(10));
+    /// assert_eq!(eval("[7, -7] // 2").unwrap(), Value::Vector(vec![Value::Int(3), Value::Int(-4)]));
+    /// assert!(matches!(eval("5 // 0"), Err(ErrorType::DivideByZero { .. })));
+    /// ```
+    pub fn floor_division(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| {
+                if rhs.as_complex()?.is_zero() {
+                    return Err(ErrorType::DivideByZero {
+                        numerator: lhs.try_as_type(ValueType::IntType),
+                    });
+                }
+                if let (Value::Rational(l), Value::Rational(r)) = (&lhs, &rhs) {
+                    if let Some(quotient) = l.checked_div(r) {
+                        return Ok(Value::from(quotient.floor()));
+                    }
+                }
+                let quotient = (lhs.as_float()? / rhs.as_float()?).floor();
+                Ok(Value::Float(quotient).try_as_type(ValueType::IntType))
+            },
+            "Floor division",
+            ValueType::RationalType,
+            false,
+        )
+    }
+
+    /// Returns the remainder of the truncated division of two values, so the
+    /// result has the same sign as the dividend. Dividing by zero returns
+    /// `ErrorType::DivideByZero`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("7 % 3").unwrap(), Value::from(1));
+    /// assert_eq!(eval("-7 % 3").unwrap(), Value::from(-1));
+    /// assert_eq!(eval("7 % (-3)").unwrap(), Value::from(1));
+    /// assert_eq!(eval("7.5 % 2").unwrap(), Value::from(1.5));
+    /// assert_eq!(eval("-7.5 % 2").unwrap(), Value::from(-1.5));
+    /// assert!(matches!(eval("5 % 0"), Err(ErrorType::DivideByZero { .. })));
+    /// ```
     pub fn modulo(self, rhs: Self) -> EvalResult<Self> {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? % rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                let divisor = rhs.as_complex()?;
+                if divisor.is_zero() {
+                    return Err(ErrorType::DivideByZero {
+                        numerator: lhs.try_as_type(ValueType::IntType),
+                    });
+                }
+                rational_or_complex(
+                    lhs,
+                    rhs,
+                    |l, r| l.checked_sub(&r.checked_mul(&l.checked_div(r)?.trunc())?),
+                    |l, r| l % r,
+                )
+            },
             "Modulo",
-            ValueType::ComplexType,
+            ValueType::RationalType,
             false,
         )
     }
@@ -286,6 +566,83 @@ impl Value {
         )
     }
 
+    pub fn bitwise_and(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| Ok(Value::Int(lhs.as_int()? & rhs.as_int()?)),
+            "Bitwise AND",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn bitwise_or(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| Ok(Value::Int(lhs.as_int()? | rhs.as_int()?)),
+            "Bitwise OR",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn bitwise_xor(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| Ok(Value::Int(lhs.as_int()? ^ rhs.as_int()?)),
+            "Bitwise XOR",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    /// Shifts the bits of an integer to the left. Results which do not fit into an
+    /// `IntValue` are returned as floats, as with a multiplication by a power of `2`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("3 << 2").unwrap(), Value::from(12));
+    /// assert_eq!(eval("-1 << 63").unwrap(), Value::from(i64::MIN));
+    /// assert_eq!(Value::from(1).left_shift(Value::from(63)).unwrap(), Value::Float(2f64.powi(63)));
+    /// assert_eq!(Value::from(3).left_shift(Value::from(62)).unwrap(), Value::Float(3.0 * 2f64.powi(62)));
+    /// assert!(eval("1 << 64").is_err());
+    /// ```
+    pub fn left_shift(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| {
+                let (lhs, amount) = (lhs.as_int()?, shift_amount(&rhs, "Left shift")?);
+                // The shift overflows if shifting back does not give the same integer
+                match lhs.checked_shl(amount).filter(|result| result >> amount == lhs) {
+                    Some(result) => Ok(Value::Int(result)),
+                    None => Ok(Value::Float(lhs as f64 * (1u64 << amount) as f64)),
+                }
+            },
+            "Left shift",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn right_shift(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| {
+                let amount = shift_amount(&rhs, "Right shift")?;
+                Ok(Value::Int(lhs.as_int()? >> amount))
+            },
+            "Right shift",
+            ValueType::IntType,
+            false,
+        )
+    }
+
     pub fn logical_and(self, rhs: Self) -> EvalResult<Self> {
         convert_and_apply(
             &self,
@@ -350,6 +707,183 @@ impl Value {
         self.equal_to(rhs)?.not()
     }
 
+    /// Returns the factorial of a non-negative integer. Results which do not fit
+    /// into an `IntValue`, that is from `21!` onwards, are returned as floats, which
+    /// overflow to infinity from `171!` onwards. Non-integer values are extended
+    /// through the gamma function, as `gamma(x + 1)`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(eval("5!").unwrap(), Value::from(120));
+    /// assert!(Value::from(170).factorial().unwrap().as_float().unwrap().is_finite());
+    /// assert_eq!(Value::from(171).factorial().unwrap(), Value::Float(f64::INFINITY));
+    /// assert_eq!(eval("1e15!").unwrap(), Value::Float(f64::INFINITY));
+    /// let half = eval("0.5!").unwrap().as_float().unwrap();
+    /// assert!((half - std::f64::consts::PI.sqrt() / 2.0).abs() < 1e-7);
+    /// ```
+    pub fn factorial(self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &Value::Int(0),
+            &mut |lhs, _| {
+                let x = lhs.as_float()?;
+                if x.fract() != 0.0 {
+                    return Value::Float(x + 1.0).gamma();
+                }
+
+                let n = lhs.as_int()?;
+                if n < 0 {
+                    return Err(ErrorType::DomainError {
+                        value: lhs,
+                        operation_name: "Factorial",
+                    });
+                }
+                // The largest factorial which fits into a float is `170!`
+                if n > 170 {
+                    return Ok(Value::Float(f64::INFINITY));
+                }
+
+                let mut product: IntValue = 1;
+                for i in 2..=n {
+                    match product.checked_mul(i) {
+                        Some(value) => product = value,
+                        None => return Ok(Value::Float((2..=n).map(|i| i as f64).product())),
+                    }
+                }
+                Ok(Value::Int(product))
+            },
+            "Factorial",
+            ValueType::FloatType,
+            false,
+        )
+    }
+
+    /// Returns the element of a vector at an index, starting from `0`. Other values
+    /// are treated as vectors with a single element.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let vector = Value::from(vec![4, 5, 6]);
+    ///
+    /// assert_eq!(vector.clone().index(Value::from(1)).unwrap(), Value::from(5));
+    /// assert_eq!(Value::from(7).index(Value::from(0)).unwrap(), Value::from(7));
+    /// assert!(matches!(vector.clone().index(Value::from(3)), Err(ErrorType::DomainError { .. })));
+    /// assert!(vector.index(Value::from(0.5)).is_err());
+    /// ```
+    pub fn index(self, index: Self) -> EvalResult<Self> {
+        let position = index.as_int()?;
+        let mut values = self.as_vector();
+
+        match usize::try_from(position) {
+            Ok(position) if position < values.len() => Ok(values.swap_remove(position)),
+            _ => Err(ErrorType::DomainError {
+                value: index,
+                operation_name: "Index",
+            }),
+        }
+    }
+
+    /// Returns the double factorial of an integer, that is the product of the positive
+    /// integers up to it with its same parity. It is `1` for `0` and `-1`, and is not
+    /// defined for other negative integers. Results which do not fit into an
+    /// `IntValue` are returned as floats.
+    pub fn double_factorial(self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &Value::Int(0),
+            &mut |lhs, _| {
+                let n = lhs.as_int()?;
+                if n < -1 {
+                    return Err(ErrorType::DomainError {
+                        value: lhs,
+                        operation_name: "Double factorial",
+                    });
+                }
+                // The largest factorial which fits into a float is `170!`
+                if n > 170 {
+                    return Ok(Value::Float(f64::INFINITY));
+                }
+
+                let mut product: IntValue = 1;
+                for i in (2..=n).rev().step_by(2) {
+                    match product.checked_mul(i) {
+                        Some(value) => product = value,
+                        None => {
+                            return Ok(Value::Float(
+                                (2..=n).rev().step_by(2).map(|i| i as f64).product(),
+                            ))
+                        }
+                    }
+                }
+                Ok(Value::Int(product))
+            },
+            "Double factorial",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    /// Returns the gamma function of a real number, computed through the Lanczos
+    /// approximation. Positive integers return the exact factorial of their
+    /// predecessor, while the poles at non-positive integers return
+    /// `ErrorType::DomainError`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(Value::from(5).gamma().unwrap(), Value::from(24));
+    /// assert!(matches!(Value::from(-2).gamma(), Err(ErrorType::DomainError { .. })));
+    /// ```
+    pub fn gamma(self) -> EvalResult<Self> {
+        const G: f64 = 7.0;
+        const COEFFICIENTS: [f64; 9] = [
+            0.999_999_999_999_809_9,
+            676.520_368_121_885_1,
+            -1_259.139_216_722_402_8,
+            771.323_428_777_653_1,
+            -176.615_029_162_140_6,
+            12.507_343_278_686_905,
+            -0.138_571_095_265_720_12,
+            9.984_369_578_019_572e-6,
+            1.505_632_735_149_311_6e-7,
+        ];
+
+        fn lanczos(x: f64) -> f64 {
+            if x < 0.5 {
+                // Reflection formula
+                return core::f64::consts::PI / ((core::f64::consts::PI * x).sin() * lanczos(1.0 - x));
+            }
+            let x = x - 1.0;
+            let t = x + G + 0.5;
+            let sum = COEFFICIENTS[1..]
+                .iter()
+                .enumerate()
+                .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+            (2.0 * core::f64::consts::PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * sum
+        }
+
+        convert_and_apply(
+            &self,
+            &Value::Int(0),
+            &mut |lhs, _| {
+                let x = lhs.as_float()?;
+                match x {
+                    x if x.fract() == 0.0 && x <= 0.0 => Err(ErrorType::DomainError {
+                        value: lhs,
+                        operation_name: "Gamma",
+                    }),
+                    x if x.fract() == 0.0 => Value::Float(x - 1.0).factorial(),
+                    x => Ok(Value::Float(lanczos(x))),
+                }
+            },
+            "Gamma",
+            ValueType::FloatType,
+            false,
+        )
+    }
+
     pub fn not(self) -> EvalResult<Self> {
         convert_and_apply(
             &self,
@@ -1,5 +1,5 @@
 use super::ErrorType::{self, *};
-use std::fmt;
+use core::fmt;
 
 impl fmt::Display for ErrorType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -9,7 +9,7 @@ impl fmt::Display for ErrorType {
                 "MATH ERROR: a function expected type {}, but a type {} was given.",
                 expected, given
             ),
-            UnknownToken { token } => write!(
+            UnknownToken { token, .. } => write!(
                 f,
                 "SYNTAX ERROR: an invalid token was provided: `{}`.",
                 token
@@ -34,14 +34,28 @@ impl fmt::Display for ErrorType {
             DivideByZero { numerator } => {
                 write!(f, "MATH ERROR: trying to divide {} by zero.", numerator)
             }
+            DomainError {
+                value,
+                operation_name,
+            } => write!(
+                f,
+                "MATH ERROR: value {} is outside the domain of operation `{}`.",
+                value, operation_name
+            ),
+            SingularMatrix => write!(f, "MATH ERROR: the matrix is singular."),
             NotAnOperator { token } => {
                 write!(f, "SYNTAX ERROR: `{}` is not a valid operator!", token)
             }
-            InvalidClosingBracket => write!(f, "SYNTAX ERROR: invalid closing bracket."),
-            MissingClosingBracket => write!(f, "SYNTAX ERROR: missing closing bracket."),
-            MissingOperatorArgument { token } => {
+            InvalidClosingBracket { .. } => write!(f, "SYNTAX ERROR: invalid closing bracket."),
+            MissingClosingBracket { .. } => write!(f, "SYNTAX ERROR: missing closing bracket."),
+            MissingOperatorArgument { token, .. } => {
                 write!(f, "SYNTAX ERROR: missing argument for operator `{}`", token)
             }
+            MissingOperator { .. } => write!(f, "SYNTAX ERROR: missing operator."),
+            AmbiguousFactorial { .. } => write!(
+                f,
+                "SYNTAX ERROR: ambiguous `!!`, write `(n!)!` or `doublefactorial(n)`."
+            ),
             FailedParse { value } => write!(f, "SYNTAX ERROR: could not parse value `{}`.", value),
             EmptyBrackets => write!(f, "SYNTAX ERROR: invalid empty brackets."),
             WrongFunctionArgumentsAmount {
@@ -82,6 +96,20 @@ impl fmt::Display for ErrorType {
             RecursionDepthLimitReached { limit } => {
                 write!(f, "INTERNAL ERROR: maximum depth reached: {}.", limit)
             }
+            CircularReference { names } => write!(
+                f,
+                "MATH ERROR: circular reference between variables `{}`.",
+                names.join("`, `")
+            ),
+            NotDifferentiable { expression } => {
+                write!(f, "MATH ERROR: cannot differentiate `{}`.", expression)
+            }
+            IterationLimitReached { limit } => {
+                write!(f, "INTERNAL ERROR: maximum iterations reached: {}.", limit)
+            }
+            StepLimitReached { limit } => {
+                write!(f, "INTERNAL ERROR: maximum evaluation steps reached: {}.", limit)
+            }
 
             ErrorDuring {
                 operation_name,
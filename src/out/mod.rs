@@ -1,6 +1,8 @@
 mod display;
 
-use crate::objects::Request;
+use core::ops::Range;
+
+use crate::{objects::Request, prelude::*};
 
 use super::{
     token::tokentype::TokenType,
@@ -13,7 +15,7 @@ pub type EvalResult<T> = Result<T, ErrorType>;
 /// Contains all possible error messages. Implements `Display`.
 ///
 /// ```
-/// use num_parser::*;
+/// use num_parser2::*;
 ///
 /// let msg = ErrorType::DivideByZero{
 ///     numerator: Value::from(2)
@@ -21,6 +23,23 @@ pub type EvalResult<T> = Result<T, ErrorType>;
 ///
 /// assert_eq!(msg, "MATH ERROR: trying to divide 2 by zero.");
 /// ```
+///
+/// Each kind of failure has its own variant, so errors can be handled programmatically:
+///
+/// ```
+/// use num_parser2::*;
+///
+/// match eval("z + 1") {
+///     Err(ErrorType::UnknownVar { var_name }) => assert_eq!(var_name, "z"),
+///     _ => unreachable!(),
+/// }
+///
+/// assert!(matches!(eval("1/0"), Err(ErrorType::DivideByZero { .. })));
+/// assert!(matches!(
+///     eval("sin(1, 2)"),
+///     Err(ErrorType::WrongFunctionArgumentsAmount { .. })
+/// ));
+/// ```
 #[derive(Debug)]
 pub enum ErrorType {
     /// A mismatched type.
@@ -29,7 +48,7 @@ pub enum ErrorType {
         given: ValueType,
     },
     /// An unknown token found while parsing the string.
-    UnknownToken { token: String },
+    UnknownToken { token: String, span: Range<usize> },
     /// A known token placed in an invalid position.
     InvalidTokenPosition { token: TokenType },
     /// A failed cast due to data loss.
@@ -46,14 +65,27 @@ pub enum ErrorType {
     },
     /// Trying to divide by zero.
     DivideByZero { numerator: Value },
+    /// A value outside of the domain of an operation.
+    DomainError {
+        value: Value,
+        operation_name: &'static str,
+    },
+    /// A singular matrix where an invertible one is needed.
+    SingularMatrix,
     /// A token which is not an operator being used as such.
     NotAnOperator { token: TokenType },
     /// An invalid closing bracket.
-    InvalidClosingBracket,
-    /// A missing closing bracket.
-    MissingClosingBracket,
+    InvalidClosingBracket { span: Range<usize> },
+    /// A missing closing bracket for the opening one.
+    MissingClosingBracket { span: Range<usize> },
     /// A missing left argument for an operator.
-    MissingOperatorArgument { token: TokenType },
+    MissingOperatorArgument { token: TokenType, span: Range<usize> },
+    /// Two adjacent factors without an operator, when implicit multiplications are
+    /// disabled.
+    MissingOperator { span: Range<usize> },
+    /// Two factorials in a row, like `5!!`, which could be mistaken for a double
+    /// factorial.
+    AmbiguousFactorial { span: Range<usize> },
     /// An error occurred while parsing a literal.
     FailedParse { value: String },
     /// Two brackets with nothing inside.
@@ -82,6 +114,14 @@ pub enum ErrorType {
     InvalidMutableContext { request: Request },
     /// Reached maximum recursion depth.
     RecursionDepthLimitReached { limit: u32 },
+    /// Variables which depend on each other.
+    CircularReference { names: Vec<String> },
+    /// An expression which cannot be differentiated.
+    NotDifferentiable { expression: String },
+    /// Reached maximum iterations amount.
+    IterationLimitReached { limit: u32 },
+    /// Reached maximum amount of evaluated expressions.
+    StepLimitReached { limit: u64 },
 
     /// An error wrapper to add additional information.
     ErrorDuring {
@@ -93,4 +133,74 @@ pub enum ErrorType {
     InternalError { message: String },
 }
 
-impl std::error::Error for ErrorType {}
+impl ErrorType {
+    /// Returns the byte range of the input the error refers to, if any.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let error = eval("3 + * 4").unwrap_err();
+    ///
+    /// assert!(matches!(error, ErrorType::MissingOperatorArgument { .. }));
+    /// assert_eq!(error.span(), Some(4..5));
+    /// assert_eq!(eval("(2 + 1").unwrap_err().span(), Some(0..1));
+    /// assert_eq!(eval("2 + 1)").unwrap_err().span(), Some(5..6));
+    /// assert_eq!(eval("é + #").unwrap_err().span(), Some(5..6));
+    /// assert_eq!(eval("1/0").unwrap_err().span(), None);
+    /// ```
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Self::UnknownToken { span, .. }
+            | Self::InvalidClosingBracket { span }
+            | Self::MissingClosingBracket { span }
+            | Self::MissingOperatorArgument { span, .. }
+            | Self::MissingOperator { span }
+            | Self::AmbiguousFactorial { span } => Some(span.clone()),
+            Self::ErrorDuring { error, .. } => error.span(),
+            _ => None,
+        }
+    }
+}
+
+/// Renders an error message followed by the input with carets under the part the
+/// error refers to. Errors with no [span](ErrorType::span) only render their message.
+///
+/// Carets are aligned by chars, so inputs containing multi-byte chars are
+/// underlined correctly.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let input = "π + * 4";
+/// let error = eval(input).unwrap_err();
+///
+/// assert_eq!(
+///     render_error(input, &error),
+///     "SYNTAX ERROR: missing argument for operator `*`\nπ + * 4\n    ^"
+/// );
+/// assert_eq!(
+///     render_error("1/0", &eval("1/0").unwrap_err()),
+///     "MATH ERROR: trying to divide 1 by zero."
+/// );
+/// ```
+pub fn render_error(input: &str, error: &ErrorType) -> String {
+    let message = error.to_string();
+
+    match error.span() {
+        Some(span) if span.end <= input.len() => {
+            let padding = input[..span.start].chars().count();
+            let width = input[span].chars().count().max(1);
+            format!("{}\n{}\n{}{}", message, input, " ".repeat(padding), "^".repeat(width))
+        }
+        _ => message,
+    }
+}
+
+impl core::error::Error for ErrorType {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::ErrorDuring { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
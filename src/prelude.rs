@@ -0,0 +1,61 @@
+//! The collections, strings and synchronization primitives used across the crate.
+//! They come from `std` by default, and from `alloc`, `hashbrown` and `spin` when
+//! the `std` feature is disabled.
+
+pub(crate) use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+
+#[cfg(feature = "std")]
+pub(crate) use std::{
+    collections::{HashMap, HashSet},
+    sync::{LazyLock, Mutex, RwLock},
+};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use {
+    self::sync::{LazyLock, Mutex, RwLock},
+    hashbrown::{HashMap, HashSet},
+    num::traits::Float,
+};
+
+/// Spin locks with the same interface as the `std` ones, so that they can be used
+/// interchangeably. They can never be poisoned.
+#[cfg(not(feature = "std"))]
+mod sync {
+    use core::convert::Infallible;
+
+    pub(crate) use spin::Lazy as LazyLock;
+
+    #[derive(Debug, Default)]
+    pub(crate) struct Mutex<T>(spin::Mutex<T>);
+
+    impl<T> Mutex<T> {
+        pub(crate) fn lock(&self) -> Result<spin::MutexGuard<'_, T>, Infallible> {
+            Ok(self.0.lock())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub(crate) struct RwLock<T>(spin::RwLock<T>);
+
+    impl<T> RwLock<T> {
+        pub(crate) const fn new(value: T) -> Self {
+            Self(spin::RwLock::new(value))
+        }
+
+        pub(crate) fn read(&self) -> Result<spin::RwLockReadGuard<'_, T>, Infallible> {
+            Ok(self.0.read())
+        }
+
+        pub(crate) fn write(&self) -> Result<spin::RwLockWriteGuard<'_, T>, Infallible> {
+            Ok(self.0.write())
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt;
+use core::fmt;
 
 use super::{
     tokentype::TokenType::{self, *},
@@ -10,7 +10,9 @@ impl fmt::Display for Token {
         match self.r#type {
             Plus | Minus | Star | Slash | Dot | Comma | OpeningBracket | ClosingBracket | Equal
             | Caret | Percentage | LessThan | GreaterThan | LessOrEqualTo | GreaterOrEqualTo
-            | DoubleEqual | DoubleAnd | DoubleOr | NotEqual | Exclamation | And | Or => {
+            | DoubleEqual | DoubleAnd | DoubleOr | NotEqual | Exclamation | And | Or
+            | DoubleLessThan | DoubleGreaterThan | DoubleSlash | Factorial | Percent | OpeningSquareBracket
+            | ClosingSquareBracket | Index => {
                 write!(f, "{}", self.r#type)
             }
 
@@ -37,15 +39,23 @@ impl fmt::Display for TokenType {
 
             LessOrEqualTo => write!(f, "<="),
             GreaterOrEqualTo => write!(f, ">="),
+            DoubleLessThan => write!(f, "<<"),
+            DoubleGreaterThan => write!(f, ">>"),
             DoubleEqual => write!(f, "=="),
             DoubleAnd => write!(f, "&&"),
             DoubleOr => write!(f, "||"),
             NotEqual => write!(f, "!="),
+            DoubleSlash => write!(f, "//"),
 
             Exclamation => write!(f, "!"),
+            Factorial => write!(f, "!"),
+            Percent => write!(f, "%"),
 
             OpeningBracket => write!(f, "("),
             ClosingBracket => write!(f, ")"),
+            OpeningSquareBracket => write!(f, "["),
+            ClosingSquareBracket => write!(f, "]"),
+            Index => write!(f, "[]"),
 
             Dot => write!(f, "."),
             Literal => write!(f, "<literal>"),
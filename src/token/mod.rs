@@ -5,12 +5,15 @@ use crate::{
     context::Context,
     function::builtin,
     out::{ErrorType, EvalResult},
+    prelude::*,
     token::tokentype::TokenType,
 };
 
 use self::tokentype::IdentifierType;
 use itertools::Itertools;
+use core::{iter::Peekable, ops::Range, str::Chars};
 
+/// A token of an input, as returned by `tokenize`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     /// The generic token type.
@@ -19,6 +22,9 @@ pub struct Token {
     pub length: usize,
     /// The token content. Only useful for literals and identifiers.
     pub value: String,
+    /// The token byte range inside the original input. Tokens which are not part of
+    /// the input, like implicit multiplications, have an empty range.
+    pub span: Range<usize>,
 }
 
 pub type TokenStream = Vec<Token>;
@@ -29,34 +35,120 @@ impl Token {
             r#type,
             length,
             value: String::from(value),
+            span: 0..0,
         }
     }
 
+    /// Sets the token byte range inside the original input.
+    fn at(mut self, span: Range<usize>) -> Self {
+        self.span = span;
+        self
+    }
+
     fn join_with(&mut self, token: &Token, r#type: TokenType) {
         self.r#type = r#type;
         self.value.extend(token.value.chars());
         self.length += token.length;
+        self.span.end = token.span.end;
     }
 }
 
 /// Builds a stream of tokens.
 pub fn build_stream(mut source: String, context: &Context) -> EvalResult<TokenStream> {
+    // The byte range of every char kept after removing whitespaces.
+    let char_spans = source
+        .char_indices()
+        .filter(|(_, c)| *c != ' ')
+        .map(|(i, c)| i..i + c.len_utf8())
+        .collect::<Vec<Range<usize>>>();
     source = remove_whitespaces(&source);
 
     let mut stream: TokenStream = vec![];
-    let mut content_iter = source.chars();
+    let mut content_iter = source.chars().peekable();
+    let mut previous: Option<char> = None;
+    let mut index = 0;
 
     while let Some(c) = content_iter.next() {
-        stream.push(tokenize(&c)?);
+        // Superscript digits are an exponent, as in `x²`
+        if let Some(digit) = superscript_digit(c) {
+            let mut exponent = String::from(digit);
+            while let Some(digit) = content_iter.peek().copied().and_then(superscript_digit) {
+                content_iter.next();
+                exponent.push(digit);
+            }
+
+            let span = char_spans[index].start..char_spans[index + exponent.len() - 1].end;
+            stream.push(Token::new(TokenType::Caret, 0, "").at(span.clone()));
+            stream.push(Token::new(TokenType::OpeningBracket, 0, "").at(span.start..span.start));
+            stream.push(Token::new(TokenType::Literal, exponent.len(), &exponent).at(span.clone()));
+            stream.push(Token::new(TokenType::ClosingBracket, 0, "").at(span.end..span.end));
+
+            previous = Some(c);
+            index += exponent.len();
+            continue;
+        }
+
+        let starts_number = !previous.is_some_and(|p| p.is_alphanumeric() || p == '.');
+        let token = match (c, content_iter.peek().copied()) {
+            ('0', Some(prefix)) if starts_number && radix(prefix).is_some() => {
+                previous = Some(prefix);
+                read_radix_literal(&mut content_iter)?
+            }
+            (digit, _) if starts_number && is_dms(digit, content_iter.clone(), context) => {
+                previous = Some('d');
+                read_dms(digit, &mut content_iter)?
+            }
+            ('e' | 'E', _)
+                if previous.is_some_and(|p| p.is_ascii_digit())
+                    && is_exponent(content_iter.clone()) =>
+            {
+                previous = Some('0');
+                read_exponent(c, &mut content_iter)
+            }
+            // Underscores between letters are part of an identifier
+            ('_', Some(next)) if previous.is_some_and(char::is_alphabetic) && next.is_alphabetic() => {
+                previous = Some(c);
+                Token::new(TokenType::Identifier(IdentifierType::Unknown), 1, "_")
+            }
+            // Digit separators are only allowed between two digits
+            ('_', next) => {
+                match (previous, next) {
+                    (Some(p), Some(n)) if p.is_ascii_digit() && n.is_ascii_digit() => (),
+                    _ => {
+                        return Err(ErrorType::FailedParse {
+                            value: previous.into_iter().chain(Some('_')).chain(next).collect(),
+                        })
+                    }
+                }
+                index += 1;
+                continue;
+            }
+            _ => {
+                previous = Some(c);
+                tokenize(&c).ok_or_else(|| ErrorType::UnknownToken {
+                    token: c.to_string(),
+                    span: char_spans[index].clone(),
+                })?
+            }
+        };
+
+        let start = char_spans[index].start;
+        index += token.length;
+        stream.push(token.at(start..char_spans[index - 1].end));
     }
 
+    stream = find_lists(&stream)?;
+    stream = find_absolute_values(&stream)?;
     stream = join_operators(&stream);
     stream = join_identifiers(&stream)?;
     stream = join_literals(&stream)?;
+    stream = join_numbered_functions(&stream);
+    stream = find_factorials(&stream)?;
+    stream = find_percentages(&stream);
     stream = format_identifiers(&stream, context);
     stream = predict_unknown_identifiers(&stream);
     stream = add_implicit_brackets(&stream)?;
-    stream = add_implicit_multiplications(&stream);
+    stream = add_implicit_multiplications(&stream, context.implicit_multiplication)?;
 
     Ok(stream)
 }
@@ -81,7 +173,10 @@ fn join_operators(stream: &TokenStream) -> TokenStream {
                 .collect::<Vec<TokenType>>();
 
             if *slice == pattern {
-                out_v.push(Token::new(replacement, pattern.len(), ""));
+                out_v.push(
+                    Token::new(replacement, pattern.len(), "")
+                        .at(stream[start_index].span.start..stream[end_index - 1].span.end),
+                );
 
                 for _ in 0..pattern.len() {
                     // Advance the iterator, consuming the replaced tokens.
@@ -110,16 +205,271 @@ fn join_operators(stream: &TokenStream) -> TokenStream {
     use TokenType::*;
 
     let mut new_stream = stream.clone();
+    new_stream = find_and_join(&new_stream, vec![LessThan, LessThan], DoubleLessThan);
+    new_stream = find_and_join(&new_stream, vec![GreaterThan, GreaterThan], DoubleGreaterThan);
     new_stream = find_and_join(&new_stream, vec![LessThan, Equal], LessOrEqualTo);
     new_stream = find_and_join(&new_stream, vec![GreaterThan, Equal], GreaterOrEqualTo);
     new_stream = find_and_join(&new_stream, vec![Equal, Equal], DoubleEqual);
     new_stream = find_and_join(&new_stream, vec![Exclamation, Equal], NotEqual);
     new_stream = find_and_join(&new_stream, vec![And, And], DoubleAnd);
     new_stream = find_and_join(&new_stream, vec![Or, Or], DoubleOr);
+    new_stream = find_and_join(&new_stream, vec![Slash, Slash], DoubleSlash);
 
     new_stream
 }
 
+/// Replaces square brackets with round ones. A square bracket following a value
+/// indexes it, and is preceded by an index operator, otherwise it opens a list,
+/// which is a union of values. Empty lists are replaced by an empty vector literal.
+fn find_lists(stream: &TokenStream) -> EvalResult<TokenStream> {
+    let mut out_stream: TokenStream = vec![];
+    // The opening brackets which have not been closed yet.
+    let mut open_brackets: Vec<TokenType> = vec![];
+
+    let mut stream_as_iter = stream.iter().peekable();
+    while let Some(token) = stream_as_iter.next() {
+        match token.r#type {
+            TokenType::OpeningBracket => {
+                open_brackets.push(token.r#type);
+                out_stream.push(token.clone());
+            }
+            TokenType::OpeningSquareBracket => {
+                let follows_value = out_stream.last().is_some_and(|previous| {
+                    matches!(
+                        previous.r#type,
+                        TokenType::Literal | TokenType::Identifier(_) | TokenType::ClosingBracket
+                    )
+                });
+
+                if follows_value {
+                    out_stream.push(Token::new(TokenType::Index, 1, "").at(token.span.clone()));
+                } else if let Some(next) = stream_as_iter
+                    .next_if(|next| next.r#type == TokenType::ClosingSquareBracket)
+                {
+                    out_stream.push(
+                        Token::new(TokenType::Literal, 2, "[]").at(token.span.start..next.span.end),
+                    );
+                    continue;
+                }
+                open_brackets.push(token.r#type);
+                out_stream.push(Token::new(TokenType::OpeningBracket, 1, "").at(token.span.clone()));
+            }
+            TokenType::ClosingBracket | TokenType::ClosingSquareBracket => {
+                let expected = match token.r#type {
+                    TokenType::ClosingBracket => TokenType::OpeningBracket,
+                    _ => TokenType::OpeningSquareBracket,
+                };
+                // Unmatched closing brackets are reported when building the tree
+                if open_brackets.last().is_some_and(|open| *open != expected) {
+                    return Err(ErrorType::InvalidClosingBracket {
+                        span: token.span.clone(),
+                    });
+                }
+                open_brackets.pop();
+                out_stream.push(Token::new(TokenType::ClosingBracket, 1, "").at(token.span.clone()));
+            }
+            _ => out_stream.push(token.clone()),
+        }
+    }
+
+    Ok(out_stream)
+}
+
+/// Replaces absolute value bars with calls to `abs`. A bar opens an absolute
+/// value if it is found where an operand is expected, otherwise it closes the
+/// last opened one. If none is open, it is kept as a bitwise or, or as a logical
+/// one if it is followed by another bar.
+fn find_absolute_values(stream: &TokenStream) -> EvalResult<TokenStream> {
+    let mut out_stream: TokenStream = vec![];
+    // The ranges of the bars opening an absolute value.
+    let mut open_bars: Vec<Range<usize>> = vec![];
+    let mut follows_value = false;
+
+    let mut stream_as_iter = stream.iter().peekable();
+    while let Some(token) = stream_as_iter.next() {
+        match token.r#type {
+            TokenType::Or if !follows_value => {
+                out_stream.push(
+                    Token::new(TokenType::Identifier(IdentifierType::Unknown), 0, "abs")
+                        .at(token.span.start..token.span.start),
+                );
+                out_stream.push(Token::new(TokenType::OpeningBracket, 1, "").at(token.span.clone()));
+                open_bars.push(token.span.clone());
+            }
+            TokenType::Or if !open_bars.is_empty() => {
+                out_stream.push(Token::new(TokenType::ClosingBracket, 1, "").at(token.span.clone()));
+                open_bars.pop();
+            }
+            TokenType::Or => {
+                out_stream.push(token.clone());
+                if let Some(next) = stream_as_iter.next_if(|next| next.r#type == TokenType::Or) {
+                    out_stream.push(next.clone());
+                }
+            }
+            _ => out_stream.push(token.clone()),
+        }
+
+        // Exclamation points keep the previous state, as they are either
+        // factorials or logical nots.
+        follows_value = match out_stream.last().map(|last| last.r#type) {
+            Some(
+                TokenType::Literal
+                | TokenType::Dot
+                | TokenType::Identifier(_)
+                | TokenType::ClosingBracket,
+            ) => true,
+            Some(TokenType::Exclamation) => follows_value,
+            _ => false,
+        };
+    }
+
+    if let Some(span) = open_bars.pop() {
+        return Err(ErrorType::MissingClosingBracket { span });
+    }
+
+    Ok(out_stream)
+}
+
+/// Joins identifiers with the following digits if they form the name of a built-in
+/// function containing digits, like `atan2`.
+fn join_numbered_functions(stream: &TokenStream) -> TokenStream {
+    let numbered_functions = builtin::get_built_in_functions_vec()
+        .iter()
+        .map(|x| x.func_identifier)
+        .filter(|name| name.chars().any(|c| c.is_ascii_digit()))
+        .collect::<Vec<&str>>();
+
+    let mut out_stream: TokenStream = vec![];
+
+    for token in stream {
+        if let Some(previous) = out_stream.last_mut() {
+            let joined = format!("{}{}", previous.value, token.value);
+            if previous.r#type == TokenType::Identifier(IdentifierType::Unknown)
+                && token.r#type == TokenType::Literal
+                && token.value.chars().all(|c| c.is_ascii_digit())
+                && numbered_functions.iter().any(|name| joined.ends_with(name))
+            {
+                previous.join_with(token, TokenType::Identifier(IdentifierType::Unknown));
+                continue;
+            }
+        }
+        out_stream.push(token.clone());
+    }
+
+    out_stream
+}
+
+/// Marks exclamation points following a value as factorials. Two factorials in a
+/// row are rejected, as `n!!` is commonly read as a double factorial.
+fn find_factorials(stream: &TokenStream) -> EvalResult<TokenStream> {
+    let mut out_stream: TokenStream = vec![];
+
+    for token in stream {
+        if let Some(previous) = out_stream.last() {
+            if previous.r#type == TokenType::Factorial && token.r#type == TokenType::Exclamation {
+                return Err(ErrorType::AmbiguousFactorial {
+                    span: previous.span.start..token.span.end,
+                });
+            }
+        }
+
+        let follows_value = out_stream.last().is_some_and(|previous: &Token| {
+            matches!(
+                previous.r#type,
+                TokenType::Literal
+                    | TokenType::Identifier(_)
+                    | TokenType::ClosingBracket
+            )
+        });
+
+        if token.r#type == TokenType::Exclamation && follows_value {
+            out_stream.push(Token::new(TokenType::Factorial, token.length, "").at(token.span.clone()));
+        } else {
+            out_stream.push(token.clone());
+        }
+    }
+
+    Ok(out_stream)
+}
+
+/// Replaces the `%` characters following a value and not followed by another one with
+/// percentages. A `-` after a `%` is a subtraction only when the value before the `%`
+/// is added or subtracted, so `100 - 10% - 5` has a percentage while `7 % -3` is a
+/// modulo. A percentage followed by `of` multiplies what follows, as in `20% of 50`.
+fn find_percentages(stream: &TokenStream) -> TokenStream {
+    let mut out_stream: TokenStream = vec![];
+
+    let mut tokens = stream.iter().peekable();
+    while let Some(token) = tokens.next() {
+        let follows_value = out_stream.last().is_some_and(ends_value);
+        let next = tokens.peek().copied();
+        let of = next.filter(|next| {
+            next.r#type == TokenType::Identifier(IdentifierType::Unknown)
+                && next.value.starts_with("of")
+        });
+        let precedes_value = next.is_some_and(|next| {
+            matches!(
+                next.r#type,
+                TokenType::Literal
+                    | TokenType::Identifier(_)
+                    | TokenType::OpeningBracket
+                    | TokenType::Exclamation
+            ) || (next.r#type == TokenType::Minus && !is_sum_operand(&out_stream))
+        });
+
+        if token.r#type != TokenType::Percentage || !follows_value || (precedes_value && of.is_none()) {
+            out_stream.push(token.clone());
+            continue;
+        }
+
+        out_stream.push(Token::new(TokenType::Percent, token.length, "").at(token.span.clone()));
+        if let Some(of) = of {
+            tokens.next();
+            let of_end = of.span.start + 2;
+            out_stream.push(Token::new(TokenType::Star, 2, "").at(of.span.start..of_end));
+            if of.value.len() > 2 {
+                out_stream.push(
+                    Token::new(of.r#type, of.value.len() - 2, &of.value[2..])
+                        .at(of_end.min(of.span.end)..of.span.end),
+                );
+            }
+        }
+    }
+
+    out_stream
+}
+
+/// Returns true if the token can be the last one of a value.
+fn ends_value(token: &Token) -> bool {
+    matches!(
+        token.r#type,
+        TokenType::Literal
+            | TokenType::Identifier(_)
+            | TokenType::ClosingBracket
+            | TokenType::Factorial
+            | TokenType::Percent
+    )
+}
+
+/// Returns true if the value at the end of the stream is the right operand of a
+/// binary `+` or `-`, as the `10` in `100 - 10`.
+fn is_sum_operand(stream: &[Token]) -> bool {
+    let mut depth = 0;
+    for (position, token) in stream.iter().enumerate().rev() {
+        match token.r#type {
+            TokenType::ClosingBracket => depth += 1,
+            TokenType::OpeningBracket if depth == 0 => return false,
+            TokenType::OpeningBracket => depth -= 1,
+            TokenType::Plus | TokenType::Minus if depth == 0 => {
+                return position.checked_sub(1).is_some_and(|previous| ends_value(&stream[previous]))
+            }
+            _ if depth == 0 && !ends_value(token) => return false,
+            _ => (),
+        }
+    }
+    false
+}
+
 fn predict_unknown_identifiers(stream: &TokenStream) -> TokenStream {
     // Return if empty.
     if stream.len() == 0 {
@@ -139,18 +489,24 @@ fn predict_unknown_identifiers(stream: &TokenStream) -> TokenStream {
         if prev.r#type == TokenType::Identifier(IdentifierType::Unknown) {
             if next_is_bracket {
                 // Categorize as function
-                out_stream.push(Token::new(
-                    TokenType::Identifier(IdentifierType::Function),
-                    prev.value.len(),
-                    &prev.value[..],
-                ));
+                out_stream.push(
+                    Token::new(
+                        TokenType::Identifier(IdentifierType::Function),
+                        prev.value.len(),
+                        &prev.value[..],
+                    )
+                    .at(prev.span.clone()),
+                );
             } else {
                 // Categorize as var
-                out_stream.push(Token::new(
-                    TokenType::Identifier(IdentifierType::Var),
-                    prev.value.len(),
-                    &prev.value[..],
-                ));
+                out_stream.push(
+                    Token::new(
+                        TokenType::Identifier(IdentifierType::Var),
+                        prev.value.len(),
+                        &prev.value[..],
+                    )
+                    .at(prev.span.clone()),
+                );
             }
         } else {
             out_stream.push(prev.clone());
@@ -190,13 +546,23 @@ fn add_implicit_brackets(stream: &TokenStream) -> EvalResult<TokenStream> {
         if prev.r#type == TokenType::Identifier(IdentifierType::Function)
             && next.r#type != TokenType::OpeningBracket
         {
-            if next.r#type == TokenType::Literal
+            if matches!(next.r#type, TokenType::Comma | TokenType::ClosingBracket) {
+                // A function passed as an argument, like in `derivative(f, 2)`
+                let mut reference = prev.clone();
+                reference.r#type = TokenType::Identifier(IdentifierType::Var);
+                out_stream.push(reference);
+            } else if next.r#type == TokenType::Literal
                 || next.r#type == TokenType::Identifier(IdentifierType::Var)
             {
                 out_stream.push(prev.clone());
-                out_stream.push(Token::new(TokenType::OpeningBracket, 1, ""));
+                out_stream.push(
+                    Token::new(TokenType::OpeningBracket, 1, "")
+                        .at(next.span.start..next.span.start),
+                );
                 out_stream.push(next.clone());
-                out_stream.push(Token::new(TokenType::ClosingBracket, 1, ""));
+                out_stream.push(
+                    Token::new(TokenType::ClosingBracket, 1, "").at(next.span.end..next.span.end),
+                );
 
                 skip_iteration = true;
             } else {
@@ -224,8 +590,16 @@ fn format_identifiers(stream: &TokenStream, context: &Context) -> TokenStream {
         if token.r#type == TokenType::Identifier(IdentifierType::Unknown) {
             let content = token.value.clone();
             let splitted = split_into_identifiers(content, context);
+            // Identifiers joined over whitespaces cannot be split into exact ranges.
+            let is_contiguous = token.span.len() == token.value.len();
+            let mut start = token.span.start;
             for (i, i_type) in splitted {
-                out_stream.push(Token::new(TokenType::Identifier(i_type), i.len(), &i[..]));
+                let span = match is_contiguous {
+                    true => start..start + i.len(),
+                    false => token.span.clone(),
+                };
+                start += i.len();
+                out_stream.push(Token::new(TokenType::Identifier(i_type), i.len(), &i[..]).at(span));
             }
         } else {
             out_stream.push(token.clone());
@@ -310,13 +684,12 @@ pub fn split_into_identifiers(input: String, context: &Context) -> Vec<(String,
                     break;
                 }
                 None => {
-                    if current.len() == 1 {
+                    if current.chars().count() == 1 {
                         out.push((current.clone(), IdentifierType::Unknown));
                         break;
                     } else {
                         // Remove one char from current and push it to to_identify
-                        to_identify.insert(0, current.chars().last().unwrap());
-                        current = current[0..current.len() - 1].to_owned();
+                        to_identify.insert(0, current.pop().unwrap());
                     }
                 }
             }
@@ -326,10 +699,12 @@ pub fn split_into_identifiers(input: String, context: &Context) -> Vec<(String,
     out
 }
 
-fn add_implicit_multiplications(stream: &TokenStream) -> TokenStream {
+/// Adds the multiplications between adjacent factors, like in `2x`. If they are not
+/// allowed, returns `ErrorType::MissingOperator` instead.
+fn add_implicit_multiplications(stream: &TokenStream, allowed: bool) -> EvalResult<TokenStream> {
     // Return if empty.
     if stream.len() == 0 {
-        return stream.clone();
+        return Ok(stream.clone());
     }
 
     let mut out_stream: TokenStream = vec![stream[0].clone()];
@@ -360,8 +735,17 @@ fn add_implicit_multiplications(stream: &TokenStream) -> TokenStream {
             || matches!(previous_token_type, Identifier(_)) && current_type == Literal
             // any identifier-any identifier
             || matches!(previous_token_type, Identifier(_)) && matches!(current_type, Identifier(_))
+            // factorial-literal, factorial-bracket or factorial-any identifier: 3!2
+            || previous_token_type == Factorial
+                && (matches!(current_type, Literal | OpeningBracket | Identifier(_)))
         {
-            out_stream.push(Token::new(TokenType::Star, 1, ""));
+            if !allowed {
+                return Err(ErrorType::MissingOperator {
+                    span: stream[index].span.clone(),
+                });
+            }
+            let start = stream[index].span.start;
+            out_stream.push(Token::new(TokenType::Star, 1, "").at(start..start));
         }
 
         out_stream.push(stream[index].clone());
@@ -369,7 +753,7 @@ fn add_implicit_multiplications(stream: &TokenStream) -> TokenStream {
         index += 1;
     }
 
-    out_stream
+    Ok(out_stream)
 }
 
 fn remove_whitespaces(string: &String) -> String {
@@ -442,27 +826,185 @@ fn join_literals(stream: &TokenStream) -> EvalResult<TokenStream> {
     Ok(joined_stream)
 }
 
-/// Returns the char corresponding token.
-fn tokenize(character: &char) -> EvalResult<Token> {
-    Ok(match character {
+/// Returns true if the chars following an `e` make it a number exponent: an `e` is
+/// an exponent only if it is followed by digits, optionally preceded by a sign. So
+/// `2e3` is `2000`, but `2e` and `2e-x` use the Euler's number `e`.
+fn is_exponent(mut chars: Peekable<Chars>) -> bool {
+    if matches!(chars.peek(), Some('+' | '-')) {
+        chars.next();
+    }
+    chars.peek().is_some_and(|c| c.is_ascii_digit())
+}
+
+/// Reads the exponent of a number in scientific notation, such as the `e-4` in
+/// `2e-4`. The returned literal gets joined with the previous digits.
+fn read_exponent(e: char, chars: &mut Peekable<Chars>) -> Token {
+    let mut value = String::from(e);
+    if let Some(sign) = chars.next_if(|c| *c == '+' || *c == '-') {
+        value.push(sign);
+    }
+    while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+        value.push(digit);
+    }
+    Token::new(TokenType::Literal, value.len(), &value)
+}
+
+/// Reads the components of an angle in degrees, minutes and seconds, such as
+/// `30d15m20s`, after its first digit. Components are optional but must keep their
+/// order, and the literal cannot be followed by other letters or digits, so that
+/// `2sin(x)` is not read as two seconds. Returns `None` if the chars do not start
+/// such a literal.
+fn scan_dms(first: char, chars: &mut Peekable<Chars>) -> Option<Vec<(char, String)>> {
+    let mut components: Vec<(char, String)> = vec![];
+    let mut number = String::from(first);
+    loop {
+        while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            number.push(c);
+        }
+        // The units are sorted alphabetically, so their order can be compared
+        match chars.peek() {
+            Some(&unit @ ('d' | 'm' | 's')) if components.last().is_none_or(|(last, _)| *last < unit) => {
+                chars.next();
+                components.push((unit, number));
+            }
+            _ => return None,
+        }
+        match chars.peek() {
+            Some(c) if c.is_ascii_digit() => number = String::new(),
+            Some(c) if c.is_alphanumeric() || *c == '.' || *c == '_' => return None,
+            _ => break,
+        }
+    }
+
+    // A single minutes or seconds component is a multiplication, as in `2m`
+    match components.len() > 1 || components[0].0 == 'd' {
+        true => Some(components),
+        false => None,
+    }
+}
+
+/// Returns true if the chars starting with the digit are an angle in degrees,
+/// minutes and seconds. User-defined variables and functions named `d`, `m` or `s`
+/// take precedence, so that `2d` multiplies `2` by `d` when `d` is declared.
+fn is_dms(first: char, mut chars: Peekable<Chars>, context: &Context) -> bool {
+    first.is_ascii_digit()
+        && scan_dms(first, &mut chars).is_some_and(|components| {
+            components.iter().all(|(unit, _)| {
+                let unit = unit.to_string();
+                !context.is_var(&unit) && !context.is_function(&unit)
+            })
+        })
+}
+
+/// Reads an angle in degrees, minutes and seconds into a literal in decimal
+/// degrees. Minutes and seconds must be less than `60`.
+fn read_dms(first: char, chars: &mut Peekable<Chars>) -> EvalResult<Token> {
+    let components = scan_dms(first, chars).unwrap_or_default();
+    let source = components
+        .iter()
+        .map(|(unit, number)| format!("{}{}", number, unit))
+        .collect::<String>();
+
+    let mut degrees = 0.0;
+    for (unit, number) in components {
+        let (value, limit, scale) = match (number.parse::<f64>(), unit) {
+            (Ok(value), 'd') => (value, f64::INFINITY, 1.0),
+            (Ok(value), 'm') => (value, 60.0, 60.0),
+            (Ok(value), _) => (value, 60.0, 3600.0),
+            (Err(_), _) => return Err(ErrorType::FailedParse { value: source }),
+        };
+        if value >= limit {
+            return Err(ErrorType::FailedParse { value: source });
+        }
+        degrees += value / scale;
+    }
+    Ok(Token::new(TokenType::Literal, source.len(), &degrees.to_string()))
+}
+
+/// Returns the digit corresponding to a superscript digit, such as the `2` of `²`.
+fn superscript_digit(c: char) -> Option<char> {
+    match c {
+        '⁰' => Some('0'),
+        '¹' => Some('1'),
+        '²' => Some('2'),
+        '³' => Some('3'),
+        '⁴'..='⁹' => char::from_digit(c as u32 - '⁴' as u32 + 4, 10),
+        _ => None,
+    }
+}
+
+/// Returns the radix of an integer literal prefix, such as the `x` in `0x1F`.
+fn radix(prefix: char) -> Option<u32> {
+    match prefix {
+        'x' => Some(16),
+        'o' => Some(8),
+        'b' => Some(2),
+        _ => None,
+    }
+}
+
+/// Reads an hexadecimal, octal or binary literal, after its leading zero. Only
+/// valid digits for the radix and digit separators are consumed: any following
+/// digit ends up joined with the literal, so that an invalid one fails to parse.
+fn read_radix_literal(chars: &mut Peekable<Chars>) -> EvalResult<Token> {
+    let prefix = chars.next().unwrap_or_default();
+    let radix = radix(prefix).unwrap_or(10);
+
+    let mut value = format!("0{}", prefix);
+    while let Some(&c) = chars.peek() {
+        if c == '_' {
+            // Digit separators are only allowed between two digits
+            chars.next();
+            value.push(c);
+            let next = chars.peek().copied().unwrap_or_default();
+            if value.len() == 3 || !next.is_digit(radix) {
+                return Err(ErrorType::FailedParse { value });
+            }
+            continue;
+        } else if !c.is_digit(radix) {
+            break;
+        }
+        value.push(c);
+        chars.next();
+    }
+    // The length includes digit separators, as it refers to the original input.
+    let length = value.len();
+    let value = value.replace('_', "");
+
+    if value.len() == 2 {
+        return Err(ErrorType::FailedParse { value });
+    }
+    Ok(Token::new(TokenType::Literal, length, &value))
+}
+
+/// Returns the char corresponding token, if any.
+fn tokenize(character: &char) -> Option<Token> {
+    Some(match character {
         '+' => Token::new(TokenType::Plus, 1, ""),
-        '-' => Token::new(TokenType::Minus, 1, ""),
-        '*' => Token::new(TokenType::Star, 1, ""),
-        '/' => Token::new(TokenType::Slash, 1, ""),
+        '-' | '−' => Token::new(TokenType::Minus, 1, ""),
+        '*' | '×' => Token::new(TokenType::Star, 1, ""),
+        '/' | '÷' => Token::new(TokenType::Slash, 1, ""),
         ',' => Token::new(TokenType::Comma, 1, ""),
         '=' => Token::new(TokenType::Equal, 1, ""),
         '^' => Token::new(TokenType::Caret, 1, ""),
         '%' => Token::new(TokenType::Percentage, 1, ""),
         '<' => Token::new(TokenType::LessThan, 1, ""),
         '>' => Token::new(TokenType::GreaterThan, 1, ""),
+        '≤' => Token::new(TokenType::LessOrEqualTo, 1, ""),
+        '≥' => Token::new(TokenType::GreaterOrEqualTo, 1, ""),
+        '≠' => Token::new(TokenType::NotEqual, 1, ""),
         '&' => Token::new(TokenType::And, 1, ""),
         '|' => Token::new(TokenType::Or, 1, ""),
         '!' => Token::new(TokenType::Exclamation, 1, ""),
 
         '.' => Token::new(TokenType::Dot, 1, "."),
 
+        '√' => Token::new(TokenType::Identifier(IdentifierType::Function), 1, "sqrt"),
+
         '(' => Token::new(TokenType::OpeningBracket, 1, ""),
         ')' => Token::new(TokenType::ClosingBracket, 1, ""),
+        '[' => Token::new(TokenType::OpeningSquareBracket, 1, ""),
+        ']' => Token::new(TokenType::ClosingSquareBracket, 1, ""),
         other => {
             let as_string = format!("{}", other);
             if other.is_numeric() {
@@ -474,9 +1016,7 @@ fn tokenize(character: &char) -> EvalResult<Token> {
                     &as_string,
                 )
             } else {
-                return Err(ErrorType::UnknownToken {
-                    token: String::from(&as_string),
-                });
+                return None;
             }
         }
     })
@@ -46,6 +46,10 @@ pub enum TokenType {
     LessOrEqualTo,
     /// A greater-than sign followed by an equal character.
     GreaterOrEqualTo,
+    /// Two less-than signs '<' characters.
+    DoubleLessThan,
+    /// Two greater-than signs '>' characters.
+    DoubleGreaterThan,
     /// A double equal '=' characters.
     DoubleEqual,
     /// Two and '&' characters.
@@ -54,14 +58,26 @@ pub enum TokenType {
     DoubleOr,
     /// An exclamation point followed by an equal character.
     NotEqual,
+    /// Two slash '/' characters.
+    DoubleSlash,
 
     /// An exclamation point '!' character.
     Exclamation,
+    /// An exclamation point '!' character following a value.
+    Factorial,
+    /// A percentage '%' character following a value and not followed by another one.
+    Percent,
 
     /// An opening bracket '(' character.
     OpeningBracket,
     /// A closing bracket ')' character.
     ClosingBracket,
+    /// An opening square bracket '[' character.
+    OpeningSquareBracket,
+    /// A closing square bracket ']' character.
+    ClosingSquareBracket,
+    /// An opening square bracket '[' character following a value, indexing it.
+    Index,
 
     /// A dot '.' character.
     Dot,
@@ -78,15 +94,15 @@ impl TokenType {
         *self == TokenType::Literal ||  // A number
         self.is_binary_operator() || // An operator
         self.is_unary_operator() ||
+        self.is_postfix_operator() ||
         self.is_union_operator()
     }
 
     pub fn is_binary_operator(&self) -> bool {
         match self {
             Plus | Minus | Star | Slash | Equal | Caret | Percentage | LessThan | GreaterThan
-            | LessOrEqualTo | GreaterOrEqualTo | DoubleAnd | DoubleOr | DoubleEqual | NotEqual => {
-                true
-            }
+            | LessOrEqualTo | GreaterOrEqualTo | DoubleAnd | DoubleOr | DoubleEqual | NotEqual
+            | And | Or | DoubleLessThan | DoubleGreaterThan | DoubleSlash | Index => true,
             _ => false,
         }
     }
@@ -98,6 +114,10 @@ impl TokenType {
         }
     }
 
+    pub fn is_postfix_operator(&self) -> bool {
+        matches!(self, Factorial | Percent)
+    }
+
     pub fn is_union_operator(&self) -> bool {
         match self {
             Comma => true,
@@ -109,12 +129,17 @@ impl TokenType {
         Ok(match self {
             Literal => 300,
             Identifier(_) => 200,
+            Index => 97,
+            Factorial | Percent => 95,
             Caret => 90,
             Exclamation => 80,
-            Star | Slash | Percentage => 70,
+            Star | Slash | DoubleSlash | Percentage => 70,
             Plus | Minus => 60,
             LessThan | LessOrEqualTo | GreaterThan | GreaterOrEqualTo => 50,
+            DoubleLessThan | DoubleGreaterThan => 45,
             DoubleEqual | NotEqual => 40,
+            And => 36,
+            Or => 34,
             DoubleAnd => 30,
             DoubleOr => 20,
             Comma => 10,
@@ -123,4 +148,14 @@ impl TokenType {
             _ => return Err(ErrorType::NotAnOperator { token: *self }),
         })
     }
+
+    /// The precedence of the token as a prefix unary operator. A negation binds
+    /// tighter than the multiplicative operators but not than `^`, so `-7 // 2` is
+    /// `(-7) // 2` while `-2^2` is `-(2^2)`.
+    pub fn prefix_precedence(&self) -> EvalResult<u16> {
+        match self {
+            Minus => Exclamation.precedence(),
+            _ => self.precedence(),
+        }
+    }
 }
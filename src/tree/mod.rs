@@ -3,6 +3,7 @@ use itertools::Itertools;
 use crate::{
     objects::Expression,
     out::{ErrorType, EvalResult},
+    prelude::*,
     token::{
         tokentype::{IdentifierType, TokenType},
         Token, TokenStream,
@@ -23,32 +24,33 @@ pub fn build_tree(stream: TokenStream) -> EvalResult<Tree> {
     // Sort by precedence
     let mut sorted_node_tokens = sort_node_tokens(&stream)?;
 
-    Ok(Tree(create_node(
-        &mut sorted_node_tokens,
-        &stream,
-        None,
-        (0, stream.len()),
-    )?))
+    match get_lowest_precedence_node_in_range(&mut sorted_node_tokens, &stream, (0, stream.len()))? {
+        Some(node) => Ok(Tree(node)),
+        // The input is empty
+        None => Ok(Tree(Node::Literal(Value::Int(0)))),
+    }
 }
 
 fn check_brackets(stream: &TokenStream) -> EvalResult<()> {
-    let mut depth = 0;
+    // The opening brackets which have not been closed yet.
+    let mut open_brackets = vec![];
     for token in stream {
         match token.r#type {
-            TokenType::OpeningBracket => depth += 1,
-            TokenType::ClosingBracket => {
-                depth -= 1;
-                if depth < 0 {
-                    // Also check for invalid brackets
-                    return Err(ErrorType::InvalidClosingBracket);
-                }
+            TokenType::OpeningBracket => open_brackets.push(token),
+            // Also check for invalid brackets
+            TokenType::ClosingBracket if open_brackets.pop().is_none() => {
+                return Err(ErrorType::InvalidClosingBracket {
+                    span: token.span.clone(),
+                });
             }
             _ => (),
         }
     }
-    match depth {
-        0 => Ok(()),
-        _ => return Err(ErrorType::MissingClosingBracket),
+    match open_brackets.pop() {
+        None => Ok(()),
+        Some(token) => Err(ErrorType::MissingClosingBracket {
+            span: token.span.clone(),
+        }),
     }
 }
 
@@ -63,6 +65,9 @@ struct TokenInfo {
     pub depth: u16,
     /// The token precedence.
     pub precedence: u16,
+    /// Whether the token is in a prefix position, that is at the start of the stream
+    /// or after an opening bracket or another operator.
+    pub prefix: bool,
 }
 
 /// Sorts all possible tokens that create nodes.
@@ -75,12 +80,24 @@ fn sort_node_tokens(stream: &TokenStream) -> EvalResult<Vec<TokenInfo>> {
         } else if token.r#type == TokenType::ClosingBracket {
             depth -= 1;
         } else if token.r#type.is_expression() {
-            let precedence = token.r#type.precedence()?;
+            let prefix = match position.checked_sub(1).map(|previous| stream[previous].r#type) {
+                None | Some(TokenType::OpeningBracket) => true,
+                Some(previous) => {
+                    previous.is_binary_operator()
+                        || previous.is_unary_operator()
+                        || previous.is_union_operator()
+                }
+            };
+            let precedence = match prefix {
+                true => token.r#type.prefix_precedence()?,
+                false => token.r#type.precedence()?,
+            };
             sorted.push(TokenInfo {
                 token: token.clone(),
                 position,
                 depth,
                 precedence,
+                prefix,
             });
         }
     }
@@ -128,17 +145,11 @@ fn create_node(
 
     // Get the node type.
     if token_info.token.r#type.is_binary_operator() && token_info.token.r#type.is_unary_operator() {
-        // In this case we need to check for both unary and binary
-        match build_binary_operator(sorted_node_tokens, stream, &token_info, range) {
-            Ok(node) => return Ok(node),
-            Err(_) => {
-                return Ok(build_unary_operator(
-                    sorted_node_tokens,
-                    stream,
-                    &token_info,
-                    range,
-                )?)
-            }
+        // In this case the position determines whether it is unary or binary
+        if token_info.prefix {
+            build_unary_operator(sorted_node_tokens, stream, &token_info, range)
+        } else {
+            build_binary_operator(sorted_node_tokens, stream, &token_info, range)
         }
     } else if token_info.token.r#type.is_binary_operator() {
         // Try just binary
@@ -156,6 +167,8 @@ fn create_node(
             &token_info,
             range,
         )?);
+    } else if token_info.token.r#type.is_postfix_operator() {
+        build_postfix_operator(sorted_node_tokens, stream, &token_info, range)
     } else if token_info.token.r#type.is_union_operator() {
         return Ok(build_union_operator(
             sorted_node_tokens,
@@ -177,7 +190,10 @@ fn create_node(
                         val.clone(),
                         get_function_parameters(sorted_node_tokens, stream, &token_info)?,
                     )),
-                    IdentifierType::Unknown => Err(ErrorType::UnknownToken { token: val.clone() }),
+                    IdentifierType::Unknown => Err(ErrorType::UnknownToken {
+                        token: val.clone(),
+                        span: token_info.token.span.clone(),
+                    }),
                 }
             }
             _ => Err(ErrorType::InternalError {
@@ -206,6 +222,7 @@ fn build_unary_operator(
                 None => {
                     return Err(ErrorType::MissingOperatorArgument {
                         token: token_info.token.r#type,
+                        span: token_info.token.span.clone(),
                     })
                 }
             },
@@ -213,16 +230,16 @@ fn build_unary_operator(
     ))
 }
 
-/// Builds a binary operator with the provided data.
-fn build_binary_operator(
+/// Builds a postfix unary operator from the provided data.
+fn build_postfix_operator(
     sorted_node_tokens: &mut Vec<TokenInfo>,
     stream: &TokenStream,
     token_info: &TokenInfo,
     range: (usize, usize),
 ) -> EvalResult<Node> {
-    Ok(Node::Binary(
+    Ok(Node::Unary(
+        token_info.token.r#type,
         Box::new(
-            // Previous node
             match get_lowest_precedence_node_in_range(
                 sorted_node_tokens,
                 stream,
@@ -232,22 +249,7 @@ fn build_binary_operator(
                 None => {
                     return Err(ErrorType::MissingOperatorArgument {
                         token: token_info.token.r#type,
-                    })
-                }
-            },
-        ),
-        token_info.token.r#type,
-        Box::new(
-            // Successive node
-            match get_lowest_precedence_node_in_range(
-                sorted_node_tokens,
-                stream,
-                (token_info.position + 1, range.1),
-            )? {
-                Some(next_node) => next_node,
-                None => {
-                    return Err(ErrorType::MissingOperatorArgument {
-                        token: token_info.token.r#type,
+                        span: token_info.token.span.clone(),
                     })
                 }
             },
@@ -255,6 +257,56 @@ fn build_binary_operator(
     ))
 }
 
+/// Builds a binary operator with the provided data. A percentage added to or
+/// subtracted from a value changes it by that percentage, so `x + y%` is built as
+/// `x*(1 + y%)`.
+fn build_binary_operator(
+    sorted_node_tokens: &mut Vec<TokenInfo>,
+    stream: &TokenStream,
+    token_info: &TokenInfo,
+    range: (usize, usize),
+) -> EvalResult<Node> {
+    let operator = token_info.token.r#type;
+    let missing_argument = || ErrorType::MissingOperatorArgument {
+        token: operator,
+        span: token_info.token.span.clone(),
+    };
+
+    // Previous node
+    let left = match get_lowest_precedence_node_in_range(
+        sorted_node_tokens,
+        stream,
+        (range.0, token_info.position),
+    )? {
+        Some(previous_node) => previous_node,
+        None => return Err(missing_argument()),
+    };
+    // Successive node
+    let right = match get_lowest_precedence_node_in_range(
+        sorted_node_tokens,
+        stream,
+        (token_info.position + 1, range.1),
+    )? {
+        Some(next_node) => next_node,
+        None => return Err(missing_argument()),
+    };
+
+    Ok(match (operator, right) {
+        (TokenType::Plus | TokenType::Minus, right @ Node::Unary(TokenType::Percent, _)) => {
+            Node::Binary(
+                Box::new(left),
+                TokenType::Star,
+                Box::new(Node::Binary(
+                    Box::new(Node::Literal(Value::Int(1))),
+                    operator,
+                    Box::new(right),
+                )),
+            )
+        }
+        (_, right) => Node::Binary(Box::new(left), operator, Box::new(right)),
+    })
+}
+
 /// Get the lowest precedence node in the range. The range is start-inclusive, end-exclusive.
 fn get_lowest_precedence_node_in_range(
     sorted_node_tokens: &mut Vec<TokenInfo>,
@@ -273,6 +325,12 @@ fn get_lowest_precedence_node_in_range(
         Ok(
             match candidates
                 .iter()
+                // A prefix unary operator can only be the root of the range if nothing
+                // precedes it, so that `2^-3` is `2^(-3)` while `-2^2` is `-(2^2)`.
+                .filter(|&x| {
+                    !(x.prefix && x.token.r#type.is_unary_operator())
+                        || candidates.iter().all(|c| c.position >= x.position)
+                })
                 .min_by_key(|&x| (x.depth, x.precedence, -(x.position as i16)))
             {
                 Some(value) => {
@@ -313,11 +371,8 @@ fn get_function_parameters(
                     ),
                 )? {
                     Some(node) => Ok(node),
-                    None => {
-                        return Err(ErrorType::MissingFunctionParameters {
-                            func_name: func_token.token.value.clone(),
-                        })
-                    }
+                    // A call with no arguments
+                    None => return Ok(vec![]),
                 }
             } else {
                 // No available token
@@ -357,7 +412,9 @@ fn get_corresponding_closing_bracket(
         }
         index += 1;
     }
-    Err(ErrorType::MissingClosingBracket)
+    Err(ErrorType::MissingClosingBracket {
+        span: stream[opening_bracket_pos].span.clone(),
+    })
 }
 
 fn build_union_operator(
@@ -1,10 +1,12 @@
 use super::{valuetype::ValueType, Value};
-use std::fmt;
+use crate::prelude::*;
+use core::fmt;
 
 impl fmt::Display for ValueType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::IntType => write!(f, "Integer"),
+            Self::RationalType => write!(f, "Rational"),
             Self::FloatType => write!(f, "Float"),
             Self::ComplexType => write!(f, "Complex"),
             Self::VectorType => write!(f, "Vector"),
@@ -18,6 +20,7 @@ impl fmt::Display for Value {
         match self {
             Self::Bool(v) => write!(f, "{}", v),
             Self::Int(v) => write!(f, "{}", v),
+            Self::Rational(v) => write!(f, "{}", v),
             Self::Float(v) => write!(f, "{}", v),
             Self::Complex(v) => write!(
                 f,
@@ -3,20 +3,50 @@ pub mod valuetype;
 
 use self::valuetype::ValueType;
 use super::out::*;
-use crate::{settings::Rounding, token::tokentype::TokenType};
-use num::complex::Complex64;
+use crate::{prelude::*, settings::Rounding, token::tokentype::TokenType};
+use num::{complex::Complex64, rational::Rational64};
+use core::cmp::Ordering;
 
 pub type IntValue = i64;
+pub type RationalValue = Rational64;
 pub type FloatValue = f64;
 pub type ComplexValue = Complex64;
 pub type VectorValue = Vec<Value>;
 pub type BoolValue = bool;
 
 /// Represent every possible output value.
+///
+/// With the `serde_support` feature, values can be serialized and deserialized, to
+/// store evaluation results:
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use num_parser2::*;
+///
+/// fn assert_serde<T: serde::Serialize + serde::de::DeserializeOwned>() {}
+/// assert_serde::<Value>();
+/// assert_serde::<ValueType>();
+/// # }
+/// ```
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Int(IntValue),
+    /// An exact fraction, always stored reduced and with a non-unit
+    /// denominator. Sums, subtractions, multiplications and divisions of
+    /// integers and fractions stay exact, while any other operation collapses
+    /// it to a float. Being exact, fractions are not rounded according to the
+    /// context `Rounding`.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// let two_thirds = eval("1/3 + 1/3").unwrap();
+    /// assert_eq!(two_thirds.to_string(), "2/3");
+    /// assert!(eval("sin(1/3)").unwrap().is_float());
+    /// ```
+    Rational(RationalValue),
     Float(FloatValue),
     Complex(ComplexValue),
     Vector(VectorValue),
@@ -28,6 +58,7 @@ impl Value {
         match self {
             Value::Float(_) => ValueType::FloatType,
             Value::Int(_) => ValueType::IntType,
+            Value::Rational(_) => ValueType::RationalType,
             Value::Complex(_) => ValueType::ComplexType,
             Value::Vector(_) => ValueType::VectorType,
             Value::Bool(_) => ValueType::BoolType,
@@ -38,6 +69,10 @@ impl Value {
         matches!(self, Value::Int(_))
     }
 
+    pub fn is_rational(&self) -> bool {
+        matches!(self, Value::Rational(_))
+    }
+
     pub fn is_float(&self) -> bool {
         matches!(self, Value::Float(_))
     }
@@ -54,11 +89,51 @@ impl Value {
         matches!(self, Value::Bool(_))
     }
 
+    /// Returns the closest `f64` to the value, or `None` if the value is not a
+    /// real number.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert_eq!(Value::from(4).as_f64(), Some(4.0));
+    /// assert_eq!(eval("1/4").unwrap().as_f64(), Some(0.25));
+    /// assert_eq!(eval("2i").unwrap().as_f64(), None);
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_float().ok()
+    }
+
+    /// Returns true if the value is a real number with no fractional part.
+    ///
+    /// ```
+    /// use num_parser2::*;
+    ///
+    /// assert!(Value::from(4.0).is_integer());
+    /// assert!(!Value::from(4.5).is_integer());
+    /// ```
+    pub fn is_integer(&self) -> bool {
+        match self.as_f64() {
+            Some(value) => value.is_finite() && value.fract() == 0.0,
+            None => false,
+        }
+    }
+
     pub fn as_int(&self) -> EvalResult<IntValue> {
         match self {
             Value::Int(n) => Ok(*n),
+            Value::Rational(n) => {
+                if n.is_integer() {
+                    Ok(n.to_integer())
+                } else {
+                    Err(ErrorType::FailedCast {
+                        value: self.clone(),
+                        from: ValueType::RationalType,
+                        to: ValueType::IntType,
+                    })
+                }
+            }
             Value::Float(n) => {
-                if n.fract() == 0.0 {
+                if n.fract() == 0.0 && *n >= IntValue::MIN as f64 && *n < IntValue::MAX as f64 {
                     Ok(*n as IntValue)
                 } else {
                     Err(ErrorType::FailedCast {
@@ -69,6 +144,8 @@ impl Value {
                 }
             }
             Value::Bool(n) => Ok(*n as i64),
+            // Avoid the lossy float conversion for large integers
+            Value::Vector(v) if v.len() == 1 => v[0].as_int(),
             _ => match self.as_float() {
                 Ok(float) => Value::Float(float).as_int(),
                 // Overwrite error with the current types
@@ -88,6 +165,7 @@ impl Value {
         match self {
             Value::Float(n) => Ok(*n),
             Value::Int(n) => Ok(*n as f64),
+            Value::Rational(n) => Ok(*n.numer() as f64 / *n.denom() as f64),
             Value::Bool(n) => Ok(*n as i64 as f64),
             Value::Complex(n) => {
                 if n.im == 0.0 {
@@ -120,6 +198,7 @@ impl Value {
             Value::Complex(n) => Ok(*n),
             Value::Float(n) => Ok(Complex64::new(*n, 0.0)),
             Value::Int(n) => Ok(Complex64::new(*n as f64, 0.0)),
+            Value::Rational(_) => Ok(Complex64::new(self.as_float()?, 0.0)),
             Value::Bool(n) => Ok(Complex64::new(*n as i64 as f64, 0.0)),
             Value::Vector(v) => {
                 if v.len() == 1 {
@@ -139,12 +218,33 @@ impl Value {
         match self {
             Value::Vector(v) => v.clone(),
             Value::Int(n) => vec![Value::Int(*n)],
+            Value::Rational(n) => vec![Value::Rational(*n)],
             Value::Float(n) => vec![Value::Float(*n)],
             Value::Complex(n) => vec![Value::Complex(*n)],
             Value::Bool(n) => vec![Value::Bool(*n)],
         }
     }
 
+    pub fn as_rational(&self) -> EvalResult<RationalValue> {
+        match self {
+            Value::Rational(n) => Ok(*n),
+            Value::Int(n) => Ok(RationalValue::from_integer(*n)),
+            Value::Bool(n) => Ok(RationalValue::from_integer(*n as i64)),
+            _ => match self.as_int() {
+                Ok(int) => Ok(RationalValue::from_integer(int)),
+                // Overwrite error with the current types
+                Err(err) => match err {
+                    ErrorType::FailedCast { value, from, to: _ } => Err(ErrorType::FailedCast {
+                        value,
+                        from,
+                        to: ValueType::RationalType,
+                    }),
+                    other => Err(other),
+                },
+            },
+        }
+    }
+
     pub fn as_bool(&self) -> EvalResult<BoolValue> {
         match self {
             Value::Bool(n) => Ok(*n),
@@ -180,6 +280,7 @@ impl Value {
         match valuetype {
             ValueType::BoolType => Ok(Value::Bool(self.as_bool()?)),
             ValueType::IntType => Ok(Value::Int(self.as_int()?)),
+            ValueType::RationalType => Ok(Value::Rational(self.as_rational()?)),
             ValueType::FloatType => Ok(Value::Float(self.as_float()?)),
             ValueType::ComplexType => Ok(Value::Complex(self.as_complex()?)),
             ValueType::VectorType => Ok(Value::Vector(self.as_vector())),
@@ -191,7 +292,18 @@ impl Value {
         match &string[..] {
             "true" => Ok(Value::Bool(true)),
             "false" => Ok(Value::Bool(false)),
+            "[]" => Ok(Value::Vector(vec![])),
             other => {
+                // Check for hexadecimal, octal and binary integers
+                for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+                    if let Some(digits) = other.strip_prefix(prefix) {
+                        return match IntValue::from_str_radix(digits, radix) {
+                            Ok(value) => Ok(Value::Int(value)),
+                            Err(_) => Err(ErrorType::FailedParse { value: string }),
+                        };
+                    }
+                }
+
                 let mut other = String::from(other);
 
                 // Check for imaginary numbers
@@ -218,6 +330,14 @@ impl Value {
                     }
                 }
 
+                // Check for scientific notation
+                if other.contains(['e', 'E']) {
+                    return match other.parse::<f64>() {
+                        Ok(value) => Ok(Value::Float(value)),
+                        Err(_) => Err(ErrorType::FailedParse { value: string }),
+                    };
+                }
+
                 // Check for floats
                 let count = other.matches(".").count();
                 if count != 0 {
@@ -255,6 +375,10 @@ impl Value {
                 },
                 ValueType::IntType => match self.as_int() {
                     Ok(value) => Value::Int(value),
+                    Err(_) => self.try_as_type(ValueType::RationalType),
+                },
+                ValueType::RationalType => match self.as_rational() {
+                    Ok(value) => Value::from(value),
                     Err(_) => self.try_as_type(ValueType::FloatType),
                 },
                 ValueType::FloatType => match self.as_float() {
@@ -270,28 +394,25 @@ impl Value {
         }
     }
 
+    /// Rounds the value according to the rounding mode. Integers are only rounded to
+    /// significant figures, while booleans and exact rationals are left unchanged.
     pub fn round(&self, rounding: Rounding) -> Self {
-        match rounding {
-            Rounding::Round(precision) => {
-                let precision = precision.clamp(0, 12);
-                let factor = 10.0f64.powi(precision as i32);
-                match self {
-                    Self::Float(v) => Value::Float((*v * factor).round() / factor),
-                    Self::Complex(c) => Value::Complex(Complex64::new(
-                        (c.re * factor).round() / factor,
-                        (c.im * factor).round() / factor,
-                    )),
-                    Self::Vector(vec) => {
-                        let mut out_vec = vec![];
-                        for val in vec {
-                            out_vec.push(val.round(rounding).clone());
-                        }
-                        Value::Vector(out_vec)
-                    }
-                    other => other.clone(),
+        match self {
+            Self::Int(v) if matches!(rounding, Rounding::SignificantFigures(_)) => {
+                Value::Int(rounding.apply(*v as FloatValue) as IntValue)
+            }
+            Self::Float(v) => Value::Float(rounding.apply(*v)),
+            Self::Complex(c) => {
+                Value::Complex(Complex64::new(rounding.apply(c.re), rounding.apply(c.im)))
+            }
+            Self::Vector(vec) => {
+                let mut out_vec = vec![];
+                for val in vec {
+                    out_vec.push(val.round(rounding));
                 }
+                Value::Vector(out_vec)
             }
-            _ => self.clone(),
+            other => other.clone(),
         }
     }
 }
@@ -308,6 +429,17 @@ impl From<IntValue> for Value {
     }
 }
 
+impl From<RationalValue> for Value {
+    /// Creates a rational value, or an integer one if the denominator is one.
+    fn from(rational: RationalValue) -> Self {
+        if rational.is_integer() {
+            Value::Int(rational.to_integer())
+        } else {
+            Value::Rational(rational)
+        }
+    }
+}
+
 impl From<FloatValue> for Value {
     fn from(float: FloatValue) -> Self {
         Value::Float(float)
@@ -340,3 +472,40 @@ impl PartialEq for Value {
         }
     }
 }
+
+/// Values are ordered by their real magnitude:
+/// - integers, rationals and bools (as 0 and 1) are compared exactly;
+/// - mixed integer and float values are compared as `f64`, so `Value::from(2)`
+///   and `Value::from(2.0)` are equal;
+/// - vectors are compared lexicographically with other vectors;
+/// - complex numbers with an imaginary part are only comparable when equal.
+///
+/// `NaN` is not comparable with anything.
+///
+/// ```
+/// use num_parser2::*;
+///
+/// let mut values = vec![Value::from(2.5), Value::from(1), eval("1/3").unwrap()];
+/// values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+///
+/// assert_eq!(values, vec![eval("1/3").unwrap(), Value::from(1), Value::from(2.5)]);
+/// assert!(Value::from(2) < Value::from(2.5));
+/// assert_eq!(Value::from(f64::NAN).partial_cmp(&Value::from(1)), None);
+/// ```
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Vector(lhs), Value::Vector(rhs)) => lhs.partial_cmp(rhs),
+            (
+                Value::Int(_) | Value::Rational(_) | Value::Bool(_),
+                Value::Int(_) | Value::Rational(_) | Value::Bool(_),
+            ) => Some(self.as_rational().ok()?.cmp(&other.as_rational().ok()?)),
+            (Value::Complex(_), _) | (_, Value::Complex(_))
+                if self.as_complex().ok()? == other.as_complex().ok()? =>
+            {
+                Some(Ordering::Equal)
+            }
+            _ => self.as_f64()?.partial_cmp(&other.as_f64()?),
+        }
+    }
+}
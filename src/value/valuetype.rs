@@ -1,10 +1,13 @@
-use crate::value::Value;
+use crate::{prelude::*, value::Value};
 
 /// Contains all possible values types.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValueType {
     /// Type for `i64`
     IntType,
+    /// Type for `num::rational::Ratio<i64>`
+    RationalType,
     /// Type for `f64`
     FloatType,
     /// Type for `num::complex::Complex`
@@ -19,6 +22,7 @@ impl Value {
     pub fn to_type(&self) -> ValueType {
         match self {
             Self::Int(_) => ValueType::IntType,
+            Self::Rational(_) => ValueType::RationalType,
             Self::Float(_) => ValueType::FloatType,
             Self::Complex(_) => ValueType::ComplexType,
             Self::Vector(_) => ValueType::VectorType,
@@ -34,9 +38,10 @@ impl ValueType {
         match self {
             Self::BoolType => 1,
             Self::IntType => 2,
-            Self::FloatType => 3,
-            Self::ComplexType => 4,
-            Self::VectorType => 5,
+            Self::RationalType => 3,
+            Self::FloatType => 4,
+            Self::ComplexType => 5,
+            Self::VectorType => 6,
         }
     }
 
@@ -0,0 +1,70 @@
+//! JavaScript bindings, available with the `wasm` feature.
+//!
+//! The [`WasmContext`] wraps a [`Context`] so that declarations persist between
+//! evaluations. Results are returned as strings and errors are thrown as strings
+//! containing the rendered error message.
+//!
+//! ```js
+//! import init, { WasmContext } from "./pkg/num_parser2.js";
+//!
+//! await init();
+//!
+//! const context = new WasmContext();
+//! context.define("f(x) = x^2 + 1");
+//! context.eval("a = 3");
+//!
+//! console.log(context.eval("f(a)")); // "10"
+//!
+//! try {
+//!     context.eval("1/0");
+//! } catch (error) {
+//!     console.log(error); // "MATH ERROR: trying to divide 1 by zero."
+//! }
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::{api, context::Context, out::render_error};
+
+/// A [`Context`] exposed to JavaScript.
+///
+/// ```
+/// use num_parser2::wasm::WasmContext;
+///
+/// let mut context = WasmContext::new();
+/// context.define("f(x) = 2x").unwrap();
+///
+/// assert_eq!(context.eval("k = 4").unwrap(), "");
+/// assert_eq!(context.eval("f(k) + 1").unwrap(), "9");
+/// ```
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct WasmContext {
+    context: Context,
+}
+
+#[wasm_bindgen]
+impl WasmContext {
+    /// Creates a wrapper around a default context.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluates the input, storing any declaration in the context. Declarations
+    /// return an empty string.
+    pub fn eval(&mut self, input: &str) -> Result<String, JsValue> {
+        match api::eval_with_mutable_context(input, &mut self.context) {
+            Ok(Some(value)) => Ok(value.to_string()),
+            Ok(None) => Ok(String::new()),
+            Err(error) => Err(JsValue::from_str(&render_error(input, &error))),
+        }
+    }
+
+    /// Adds a function or variable declaration to the context.
+    pub fn define(&mut self, input: &str) -> Result<(), JsValue> {
+        self.context
+            .define(input)
+            .map_err(|error| JsValue::from_str(&render_error(input, &error)))
+    }
+}